@@ -15,7 +15,7 @@ async fn main() {
         .route(hello)
         .middleware(BodyLimit(1024 * 1024 * 2));
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[derive(Form, Debug)]
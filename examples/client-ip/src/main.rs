@@ -0,0 +1,23 @@
+use mincat::{
+    extract::{ClientIp, ConnectInfo, TrustedProxies},
+    http::{get, Router},
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello);
+
+    let trusted_proxies = TrustedProxies::new(["127.0.0.1", "10.0.0.0/8"]).unwrap();
+
+    mincat::router(router)
+        .state(trusted_proxies)
+        .run("127.0.0.1:3000")
+        .await
+        .unwrap();
+}
+
+#[get("/hello")]
+async fn hello(info: ConnectInfo, ClientIp(ip): ClientIp) -> String {
+    dbg!(info);
+    format!("hello {ip}")
+}
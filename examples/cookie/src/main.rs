@@ -20,7 +20,8 @@ async fn main() {
     mincat::router(router)
         .state(CookieKey::from("xxxxxxxx"))
         .run("127.0.0.1:3000")
-        .await;
+        .await
+        .unwrap();
 }
 
 #[get("/hello1")]
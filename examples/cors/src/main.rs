@@ -13,7 +13,7 @@ async fn main() {
             .allow_credentials(false),
     );
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello")]
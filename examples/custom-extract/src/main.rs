@@ -4,7 +4,7 @@ use mincat::http::{get, FromRequestParts, Parts, Router, StatusCode};
 async fn main() {
     let router = Router::new().route(hello);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello")]
@@ -16,7 +16,7 @@ async fn hello(HeaderUserName(user_name): HeaderUserName) -> &'static str {
 struct HeaderUserName(String);
 
 #[async_trait::async_trait]
-impl FromRequestParts for HeaderUserName {
+impl<S> FromRequestParts<S> for HeaderUserName {
     type Error = (StatusCode, &'static str);
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 async fn main() {
     let router = Router::new().route(hello);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use mincat::http::{get, Router};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello).route(slow);
+
+    let report = mincat::router(router)
+        .drain_timeout(Duration::from_secs(10))
+        .run_with_shutdown("127.0.0.1:3000", async {
+            tokio::signal::ctrl_c().await.unwrap();
+        })
+        .await
+        .unwrap();
+
+    dbg!(report);
+}
+
+#[get("/hello")]
+async fn hello() -> &'static str {
+    "hello word"
+}
+
+#[get("/slow")]
+async fn slow() -> &'static str {
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    "slow hello word"
+}
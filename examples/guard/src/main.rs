@@ -0,0 +1,54 @@
+use mincat::http::{
+    get,
+    guard::{Accept, ContentType, Header, Query},
+    post, Router,
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new()
+        .route(user_v1)
+        .route(user_v2)
+        .route(upload_json)
+        .route(upload_form)
+        .route(search_debug)
+        .route(search)
+        .route(beta);
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/user", guard = Accept::new("application/vnd.x.v1+json"))]
+async fn user_v1() -> &'static str {
+    "user v1"
+}
+
+#[get("/user", guard = Accept::new("application/vnd.x.v2+json"))]
+async fn user_v2() -> &'static str {
+    "user v2"
+}
+
+#[post("/upload", guard = ContentType::new("application/json"))]
+async fn upload_json() -> &'static str {
+    "json"
+}
+
+#[post("/upload", guard = ContentType::new("application/x-www-form-urlencoded"))]
+async fn upload_form() -> &'static str {
+    "form"
+}
+
+#[get("/search", guard = Query::new("debug"))]
+async fn search_debug() -> &'static str {
+    "search with debug"
+}
+
+#[get("/search")]
+async fn search() -> &'static str {
+    "search"
+}
+
+#[get("/beta", guard = Header::new("x-beta", "1"))]
+async fn beta() -> &'static str {
+    "beta"
+}
@@ -4,7 +4,7 @@ use mincat::http::{get, Router};
 async fn main() {
     let router = Router::new().route(hello);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello")]
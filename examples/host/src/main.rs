@@ -0,0 +1,37 @@
+use mincat::{
+    extract::HostParams,
+    http::{get, Router},
+};
+
+#[tokio::main]
+async fn main() {
+    let mut app = mincat::App::new()
+        .router(Router::new().route(home))
+        .host("api.localhost", Router::new().route(api))
+        .host("admin.localhost", Router::new().route(admin))
+        .host(":tenant.localhost", Router::new().route(tenant));
+
+    print!("{}", app.routes());
+
+    app.run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/")]
+async fn home() -> &'static str {
+    "home"
+}
+
+#[get("/")]
+async fn api() -> &'static str {
+    "api"
+}
+
+#[get("/")]
+async fn admin() -> &'static str {
+    "admin"
+}
+
+#[get("/")]
+async fn tenant(host: HostParams) -> String {
+    format!("tenant {}", host.get("tenant").unwrap_or_default())
+}
@@ -10,9 +10,13 @@ async fn main() {
         .with_target(false)
         .init();
 
-    let router = Router::new().route(hello).middleware(HttpLog);
+    let router = Router::new().route(hello);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router)
+        .middleware(HttpLog)
+        .run("127.0.0.1:3000")
+        .await
+        .unwrap();
 }
 
 #[get("/hello")]
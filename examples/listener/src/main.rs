@@ -0,0 +1,28 @@
+use mincat::{
+    http::{get, Router},
+    Listener,
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello);
+
+    let tcp = Listener::bind("127.0.0.1:3000").await.unwrap();
+
+    let std_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    dbg!(std_listener.local_addr().unwrap());
+    let std_tcp = Listener::try_from(std_listener).unwrap();
+
+    let _ = std::fs::remove_file("/tmp/mincat.sock");
+    let unix = Listener::bind_unix("/tmp/mincat.sock").unwrap();
+
+    mincat::router(router)
+        .serve([tcp, std_tcp, unix])
+        .await
+        .unwrap();
+}
+
+#[get("/hello")]
+async fn hello() -> &'static str {
+    "hello word"
+}
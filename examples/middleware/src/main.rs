@@ -20,7 +20,11 @@ async fn main() {
         .route(hello)
         .middleware(middleware6);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router)
+        .middleware(middleware7)
+        .run("127.0.0.1:3000")
+        .await
+        .unwrap();
 }
 
 #[get("/hello")]
@@ -76,3 +80,11 @@ async fn middleware6(request: Request, next: Next) -> Response {
     dbg!("middleware6 end");
     response
 }
+
+#[middleware]
+async fn middleware7(request: Request, next: Next) -> Response {
+    dbg!("middleware7 start");
+    let response = next.run(request).await;
+    dbg!("middleware7 end");
+    response
+}
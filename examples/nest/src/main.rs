@@ -0,0 +1,44 @@
+use mincat::{
+    extract::{MatchedPath, NestedPath, Path},
+    http::{get, Request, Response, Router},
+    middleware::{middleware, Next},
+};
+
+#[tokio::main]
+async fn main() {
+    let v1 = Router::new().route(user).route(users);
+
+    let api = Router::new()
+        .nest("/v1", v1)
+        .fallback(api_not_found)
+        .middleware(api_log);
+
+    let router = Router::new().route(index).nest("/api", api);
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/")]
+async fn index() -> &'static str {
+    "index"
+}
+
+#[get("/users")]
+async fn users(nested: NestedPath) -> String {
+    format!("users nested at {}", nested.as_str())
+}
+
+#[get("/users/:id")]
+async fn user(Path((id,)): Path<(String,)>, matched: MatchedPath) -> String {
+    format!("user {id} matched {}", matched.as_str())
+}
+
+async fn api_not_found(nested: NestedPath) -> String {
+    format!("no api route under {}", nested.as_str())
+}
+
+#[middleware]
+async fn api_log(request: Request, next: Next) -> Response {
+    println!("api request: {}", request.uri());
+    next.run(request).await
+}
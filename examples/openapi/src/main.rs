@@ -0,0 +1,89 @@
+use mincat::{
+    extract::{
+        form::{Form, FormData, FormFile},
+        Json, Path, Query,
+    },
+    http::{get, post, Router},
+    openapi::{OpenApiRoute, SwaggerUi, ToSchema},
+};
+use serde::{Deserialize, Serialize};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new()
+        .route(get_user)
+        .route(list_users)
+        .route(create_user)
+        .route(upload_avatar);
+
+    let openapi = router.openapi().title("users").version("1.0.0");
+    println!("{openapi}");
+
+    let router = router
+        .clone()
+        .route(OpenApiRoute::new("/openapi.json", &openapi))
+        .route(SwaggerUi::new("/docs", "/openapi.json"));
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+struct User {
+    id: u64,
+    name: String,
+    role: Role,
+    email: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+enum Role {
+    #[serde(rename = "admin")]
+    Admin,
+    #[serde(rename = "member")]
+    Member,
+}
+
+#[derive(Clone, Deserialize, ToSchema)]
+struct Pagination {
+    page: u32,
+    per_page: Option<u32>,
+}
+
+#[derive(Form, ToSchema)]
+struct Avatar {
+    description: String,
+    file: FormFile,
+}
+
+/// Get a user
+///
+/// Looks up a single user by id.
+#[get("/users/:id", name = "get_user")]
+async fn get_user(Path((id,)): Path<(u64,)>) -> Json<User> {
+    Json(User {
+        id,
+        name: "mincat".to_string(),
+        role: Role::Member,
+        email: None,
+    })
+}
+
+/// List users
+#[get("/users")]
+async fn list_users(Query(pagination): Query<Pagination>) -> Json<Vec<User>> {
+    let _ = (pagination.page, pagination.per_page);
+    Json(vec![])
+}
+
+/// Create a user
+#[post("/users")]
+async fn create_user(Json(user): Json<User>) -> Result<Json<User>, String> {
+    Ok(Json(user))
+}
+
+/// Upload an avatar
+#[post("/users/:id/avatar")]
+async fn upload_avatar(Path((id,)): Path<(u64,)>, FormData(avatar): FormData<Avatar>) -> String {
+    format!("{id} {} {}", avatar.description, avatar.file.bytes().len())
+}
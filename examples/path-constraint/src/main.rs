@@ -0,0 +1,31 @@
+use mincat::{
+    extract::Path,
+    http::{get, Router},
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new()
+        .route(user_by_id)
+        .route(user_by_name)
+        .route(text_file);
+
+    print!("{}", router.routes());
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/users/:id<u64>")]
+async fn user_by_id(Path((id,)): Path<(u64,)>) -> String {
+    format!("user id {id}")
+}
+
+#[get("/users/:name<[a-z][a-z0-9_]*>")]
+async fn user_by_name(Path((name,)): Path<(String,)>) -> String {
+    format!("user name {name}")
+}
+
+#[get("/files/*path<.+\\.txt>")]
+async fn text_file(Path((path,)): Path<(String,)>) -> String {
+    format!("text file {path}")
+}
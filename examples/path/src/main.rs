@@ -1,5 +1,5 @@
 use mincat::{
-    extract::Path,
+    extract::{Path, PathFields},
     http::{get, Router},
 };
 use serde::Deserialize;
@@ -9,7 +9,7 @@ use std::collections::HashMap;
 async fn main() {
     let router = Router::new().route(hello1).route(hello2).route(hello3);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello1/:id/:name")]
@@ -24,7 +24,7 @@ async fn hello2(Path(path): Path<HashMap<String, String>>) -> &'static str {
     "hello word"
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, PathFields)]
 struct PathParams {
     id: u64,
     name: String,
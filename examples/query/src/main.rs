@@ -10,7 +10,7 @@ async fn main() {
     // client url = /hello?id=2&name=lucy
     let router = Router::new().route(hello1).route(hello2);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello1")]
@@ -0,0 +1,39 @@
+use mincat::{
+    http::{get, header, IntoResponse, Parts, Request, Response, Router, StatusCode},
+    middleware::{middleware, HttpLog, Next},
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello).route(admin);
+
+    print!("{}", router.routes());
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/hello", middleware = [auth, HttpLog], name = "hello")]
+async fn hello() -> &'static str {
+    "hello"
+}
+
+#[get("/admin", guard = is_admin, middleware = [HttpLog])]
+async fn admin() -> &'static str {
+    "admin"
+}
+
+fn is_admin(parts: &Parts) -> bool {
+    parts
+        .headers
+        .get("x-role")
+        .is_some_and(|role| role == "admin")
+}
+
+#[middleware]
+async fn auth(request: Request, next: Next) -> Response {
+    if !request.headers().contains_key(header::AUTHORIZATION) {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    next.run(request).await
+}
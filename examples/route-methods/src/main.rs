@@ -0,0 +1,43 @@
+use mincat::{
+    http::{any, route, trace, Method, Request, Response, Router},
+    middleware::{middleware, Next},
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new()
+        .route(item.middleware(log))
+        .route(cache)
+        .route(echo_trace)
+        .route(everything);
+
+    print!("{}", router.routes());
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[route(GET, POST, path = "/item")]
+async fn item(method: Method) -> String {
+    format!("item {method}")
+}
+
+#[route(PURGE, path = "/cache")]
+async fn cache() -> &'static str {
+    "purged"
+}
+
+#[trace("/trace")]
+async fn echo_trace() -> &'static str {
+    "trace"
+}
+
+#[any("/any")]
+async fn everything(method: Method) -> String {
+    format!("any {method}")
+}
+
+#[middleware]
+async fn log(request: Request, next: Next) -> Response {
+    println!("{} {}", request.method(), request.uri());
+    next.run(request).await
+}
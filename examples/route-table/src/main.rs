@@ -0,0 +1,45 @@
+use mincat::{
+    extract::MatchedPath,
+    http::{get, post, Request, Response, Router},
+    middleware::{middleware, Next},
+};
+
+#[tokio::main]
+async fn main() {
+    let api = Router::new().route(user).route(create_user);
+    let router = Router::new()
+        .route(index)
+        .nest("/api", api)
+        .middleware(metrics);
+
+    print!("{}", router.routes());
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[middleware]
+async fn metrics(request: Request, next: Next) -> Response {
+    let label = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let response = next.run(request).await;
+    println!("{label} -> {}", response.status());
+    response
+}
+
+#[get("/")]
+async fn index(path: MatchedPath) -> String {
+    format!("matched {}", path.as_str())
+}
+
+#[get("/users/:id", name = "user")]
+async fn user(path: MatchedPath) -> String {
+    format!("matched {}", path.as_str())
+}
+
+#[post("/users")]
+async fn create_user() -> &'static str {
+    "created"
+}
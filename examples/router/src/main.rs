@@ -1,4 +1,4 @@
-use mincat::http::{get, Router};
+use mincat::http::{get, Router, StatusCode};
 
 #[tokio::main]
 async fn main() {
@@ -8,9 +8,14 @@ async fn main() {
 
     let router3 = Router::new().group("/api", Router::new().route(hello3));
 
-    let router = Router::new().merge(router1).merge(router2).merge(router3);
+    let router = Router::new()
+        .merge(router1)
+        .merge(router2)
+        .merge(router3)
+        .fallback(not_found)
+        .method_not_allowed_fallback(method_not_allowed);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello1")]
@@ -27,3 +32,11 @@ async fn hello2() -> &'static str {
 async fn hello3() -> &'static str {
     "hello word3"
 }
+
+async fn not_found() -> (StatusCode, &'static str) {
+    (StatusCode::NOT_FOUND, "nothing here")
+}
+
+async fn method_not_allowed() -> (StatusCode, &'static str) {
+    (StatusCode::METHOD_NOT_ALLOWED, "method not allowed")
+}
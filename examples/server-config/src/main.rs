@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use mincat::{
+    http::{get, Router},
+    ServerConfig,
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello);
+
+    let config = ServerConfig::new()
+        .header_read_timeout(Duration::from_secs(5))
+        .keep_alive_timeout(Duration::from_secs(60))
+        .http2_max_concurrent_streams(100)
+        .max_connections(1024)
+        .max_header_size(16 * 1024);
+
+    mincat::router(router)
+        .server_config(config)
+        .run("127.0.0.1:3000")
+        .await
+        .unwrap();
+}
+
+#[get("/hello")]
+async fn hello() -> &'static str {
+    "hello world"
+}
@@ -59,7 +59,8 @@ async fn main() {
     mincat::router(router)
         .state(CookieKey::from("xxxx"))
         .run("127.0.0.1:3000")
-        .await;
+        .await
+        .unwrap();
 }
 
 #[get("/hello")]
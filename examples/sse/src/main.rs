@@ -10,7 +10,7 @@ use tokio_stream::StreamExt;
 async fn main() {
     let router = Router::new().route(hello);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[get("/hello")]
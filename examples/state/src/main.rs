@@ -21,7 +21,8 @@ async fn main() {
     mincat::router(router)
         .state(App::default())
         .run("127.0.0.1:3000")
-        .await;
+        .await
+        .unwrap();
 }
 
 #[get("/hello")]
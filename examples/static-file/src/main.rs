@@ -10,5 +10,5 @@ async fn main() {
         .unwrap();
     let router = Router::new().route(static_dir_route);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
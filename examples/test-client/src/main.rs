@@ -0,0 +1,53 @@
+use mincat::{
+    extract::{
+        cookie::{Cookie, CookieJar},
+        Json,
+    },
+    http::{get, post, Router},
+    test::TestClient,
+};
+use serde::{Deserialize, Serialize};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello).route(echo).route(login);
+    let client = TestClient::new(router);
+
+    let res = client.get("/hello").send().await;
+    dbg!(res.status(), res.text().await);
+
+    let data = Data {
+        id: 1,
+        name: "mincat".to_string(),
+    };
+    let res = client.post("/echo").json(&data).send().await;
+    dbg!(res.json::<Data>().await);
+
+    client.get("/login").send().await;
+    let res = client.get("/hello").send().await;
+    dbg!(client.cookie("user"), res.text().await);
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+struct Data {
+    id: u64,
+    name: String,
+}
+
+#[get("/hello")]
+async fn hello(cookie: CookieJar) -> String {
+    match cookie.get("user") {
+        Some(user) => format!("hello {}", user.value()),
+        None => "hello word".to_string(),
+    }
+}
+
+#[post("/echo")]
+async fn echo(Json(data): Json<Data>) -> Json<Data> {
+    Json(data)
+}
+
+#[get("/login")]
+async fn login(cookie: CookieJar) -> CookieJar {
+    cookie.add(Cookie::new("user", "mincat"))
+}
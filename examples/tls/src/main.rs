@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+use mincat::{
+    http::{get, Router},
+    tls::TlsConfig,
+};
+
+#[tokio::main]
+async fn main() {
+    let router = Router::new().route(hello);
+
+    let config = TlsConfig::from_pem_file(
+        "examples/tls/self_signed_certs/cert.pem",
+        "examples/tls/self_signed_certs/key.pem",
+    )
+    .unwrap()
+    .reload_interval(Duration::from_secs(60));
+
+    mincat::router(router)
+        .run_tls("127.0.0.1:3000", config)
+        .await
+        .unwrap();
+}
+
+#[get("/hello")]
+async fn hello() -> &'static str {
+    "hello word"
+}
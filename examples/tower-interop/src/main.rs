@@ -0,0 +1,56 @@
+use std::{convert::Infallible, time::Duration};
+
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use mincat::{
+    http::{get, header, IntoResponse, Method, Request, Route, Router, StatusCode},
+    tower::{LayerMiddleware, ServiceHandler},
+    App,
+};
+use tokio::net::TcpListener;
+use tower::service_fn;
+use tower_http::{set_header::SetResponseHeaderLayer, timeout::TimeoutLayer};
+
+#[tokio::main]
+async fn main() {
+    let legacy = service_fn(|request: Request| async move {
+        Ok::<_, Infallible>(format!("legacy {}", request.uri()).into_response())
+    });
+
+    let router = Router::new()
+        .route(hello)
+        .route(Route::init(
+            Method::GET,
+            "/legacy",
+            ServiceHandler::new(legacy),
+        ))
+        .fallback(ServiceHandler::new(legacy))
+        .middleware(LayerMiddleware::new(SetResponseHeaderLayer::overriding(
+            header::SERVER,
+            header::HeaderValue::from_static("mincat"),
+        )))
+        .middleware(LayerMiddleware::new(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            Duration::from_secs(10),
+        )));
+
+    let app = App::from(router);
+    let listener = TcpListener::bind("127.0.0.1:3000").await.unwrap();
+    loop {
+        let (stream, _) = listener.accept().await.unwrap();
+        let service = TowerToHyperService::new(app.clone());
+        tokio::spawn(async move {
+            let _ = Builder::new(TokioExecutor::new())
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+#[get("/hello")]
+async fn hello() -> &'static str {
+    "hello"
+}
@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use mincat::{
+    extract::{FromRef, State},
+    http::{get, Router},
+    App,
+};
+
+#[derive(Clone)]
+struct AppState {
+    name: String,
+    counter: Counter,
+}
+
+#[derive(Clone, Default)]
+struct Counter(Arc<AtomicUsize>);
+
+impl FromRef<AppState> for Counter {
+    fn from_ref(state: &AppState) -> Self {
+        state.counter.clone()
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let router = Router::<AppState>::default().route(hello).route(counter);
+
+    let state = AppState {
+        name: "mincat".to_string(),
+        counter: Counter::default(),
+    };
+
+    App::with_state(state)
+        .router(router)
+        .run("127.0.0.1:3000")
+        .await
+        .unwrap();
+}
+
+#[get("/hello")]
+async fn hello(State(state): State<AppState>) -> String {
+    format!("hello {}", state.name)
+}
+
+#[get("/count")]
+async fn counter(State(Counter(value)): State<Counter>) -> String {
+    let count = value.fetch_add(1, Ordering::SeqCst) + 1;
+    format!("count {count}")
+}
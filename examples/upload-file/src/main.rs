@@ -8,7 +8,7 @@ use tokio::io::AsyncWriteExt;
 async fn main() {
     let router = Router::new().route(hello1).route(hello2);
 
-    mincat::router(router).run("127.0.0.1:3000").await;
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
 }
 
 #[derive(Form, Debug)]
@@ -0,0 +1,34 @@
+use mincat::{
+    extract::{Path, UrlFor},
+    http::{get, Route, Router},
+};
+
+#[tokio::main]
+async fn main() {
+    let api = Router::new()
+        .route(user)
+        .route(Route::from(file).name("file"));
+
+    let router = Router::new().route(index).nest("/api", api);
+
+    println!("{:?}", router.url_for("user", [("id", "1")]));
+
+    mincat::router(router).run("127.0.0.1:3000").await.unwrap();
+}
+
+#[get("/")]
+async fn index(url: UrlFor) -> String {
+    let user_url = url.url_for("user", [("id", "hello world")]).unwrap();
+    let file_url = url.url_for("file", [("path", "docs/read me.txt")]).unwrap();
+    format!("{user_url}\n{file_url}")
+}
+
+#[get("/users/:id", name = "user")]
+async fn user(Path((id,)): Path<(String,)>) -> String {
+    format!("user {id}")
+}
+
+#[get("/files/*path")]
+async fn file(Path((path,)): Path<(String,)>) -> String {
+    format!("file {path}")
+}
@@ -0,0 +1,155 @@
+use std::fmt::Debug;
+
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+
+use mincat_pattern::{check_constraint_type, split_constraints};
+
+fn type_checker(ty: &str) -> Option<fn(&str) -> bool> {
+    let checker: fn(&str) -> bool = match ty {
+        "bool" => |value| value.parse::<bool>().is_ok(),
+        "i8" => |value| value.parse::<i8>().is_ok(),
+        "i16" => |value| value.parse::<i16>().is_ok(),
+        "i32" => |value| value.parse::<i32>().is_ok(),
+        "i64" => |value| value.parse::<i64>().is_ok(),
+        "i128" => |value| value.parse::<i128>().is_ok(),
+        "isize" => |value| value.parse::<isize>().is_ok(),
+        "u8" => |value| value.parse::<u8>().is_ok(),
+        "u16" => |value| value.parse::<u16>().is_ok(),
+        "u32" => |value| value.parse::<u32>().is_ok(),
+        "u64" => |value| value.parse::<u64>().is_ok(),
+        "u128" => |value| value.parse::<u128>().is_ok(),
+        "usize" => |value| value.parse::<usize>().is_ok(),
+        "f32" => |value| value.parse::<f32>().is_ok(),
+        "f64" => |value| value.parse::<f64>().is_ok(),
+        _ => return None,
+    };
+
+    Some(checker)
+}
+
+#[derive(Clone)]
+enum Kind {
+    Type(fn(&str) -> bool),
+    Regex(Regex),
+}
+
+#[derive(Clone)]
+pub struct PathConstraint {
+    name: String,
+    source: String,
+    kind: Kind,
+}
+
+impl PathConstraint {
+    fn new(name: &str, source: &str) -> Result<Self, String> {
+        check_constraint_type(name, source)?;
+        let kind = match type_checker(source) {
+            Some(checker) => Kind::Type(checker),
+            None => Regex::new(&format!("^(?:{source})$"))
+                .map(Kind::Regex)
+                .map_err(|e| format!("invalid constraint for `{name}`: {e}"))?,
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            kind,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn matches(&self, value: &str) -> bool {
+        match &self.kind {
+            Kind::Type(checker) => checker(value),
+            Kind::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl PartialEq for PathConstraint {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.source == other.source
+    }
+}
+
+impl Eq for PathConstraint {}
+
+impl Debug for PathConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}<{}>", self.name, self.source)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PathConstraints(Vec<PathConstraint>);
+
+impl PathConstraints {
+    pub fn parse(pattern: &str) -> Result<(String, Self), String> {
+        let (path, constraints) = split_constraints(pattern)?;
+        let constraints = constraints
+            .iter()
+            .map(|(name, source)| PathConstraint::new(name, source))
+            .collect::<Result<_, _>>()?;
+
+        Ok((path, Self(constraints)))
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PathConstraint> {
+        self.0.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn extend(&mut self, constraints: PathConstraints) {
+        self.0.extend(constraints.0);
+    }
+
+    pub fn pattern(&self, path: &str) -> String {
+        path.split('/')
+            .map(|segment| {
+                let constraint = segment.find([':', '*']).and_then(|start| {
+                    let name = &segment[start + 1..];
+                    self.0.iter().find(|constraint| constraint.name == name)
+                });
+
+                match constraint {
+                    Some(constraint) => format!("{segment}<{}>", constraint.source),
+                    None => segment.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    pub fn check(&self, params: &[(&str, &str)]) -> bool {
+        self.0.iter().all(|constraint| {
+            let Some((_, value)) = params.iter().find(|(name, _)| *name == constraint.name) else {
+                return false;
+            };
+
+            percent_decode_str(value)
+                .decode_utf8()
+                .is_ok_and(|value| constraint.matches(&value))
+        })
+    }
+}
+
+pub(crate) fn normalize_pattern(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.find([':', '*']) {
+            Some(start) => &segment[..=start],
+            None => segment,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,247 @@
+use std::{fmt::Debug, sync::Arc};
+
+use http::{header, HeaderName, HeaderValue, StatusCode};
+
+use crate::request::Parts;
+
+pub trait Guard: Send + Sync + 'static {
+    fn check(&self, parts: &Parts) -> bool;
+
+    fn rejection(&self) -> StatusCode {
+        StatusCode::NOT_FOUND
+    }
+
+    fn quality(&self, _parts: &Parts) -> Option<f32> {
+        None
+    }
+}
+
+impl<Func> Guard for Func
+where
+    Func: Fn(&Parts) -> bool + Send + Sync + 'static,
+{
+    fn check(&self, parts: &Parts) -> bool {
+        self(parts)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Guards(Vec<Arc<dyn Guard>>);
+
+impl Guards {
+    pub fn push<G>(&mut self, guard: G)
+    where
+        G: Guard,
+    {
+        self.0.push(Arc::new(guard));
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn check(&self, parts: &Parts) -> Result<(), StatusCode> {
+        match self.0.iter().find(|guard| !guard.check(parts)) {
+            Some(guard) => Err(guard.rejection()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn quality(&self, parts: &Parts) -> Option<f32> {
+        self.0
+            .iter()
+            .filter_map(|guard| guard.quality(parts))
+            .reduce(f32::min)
+    }
+}
+
+impl Debug for Guards {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Guards").field(&self.0.len()).finish()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Header {
+    name: HeaderName,
+    value: HeaderValue,
+}
+
+impl Header {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: HeaderName::try_from(name).expect("invalid header name"),
+            value: HeaderValue::try_from(value).expect("invalid header value"),
+        }
+    }
+}
+
+impl Guard for Header {
+    fn check(&self, parts: &Parts) -> bool {
+        parts
+            .headers
+            .get_all(&self.name)
+            .iter()
+            .any(|value| value == self.value)
+    }
+}
+
+fn essence(media_type: &str) -> &str {
+    media_type.split(';').next().unwrap_or_default().trim()
+}
+
+#[derive(Clone, Debug)]
+pub struct ContentType(String);
+
+impl ContentType {
+    pub fn new(media_type: &str) -> Self {
+        Self(essence(media_type).to_ascii_lowercase())
+    }
+}
+
+impl Guard for ContentType {
+    fn check(&self, parts: &Parts) -> bool {
+        parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| essence(value).eq_ignore_ascii_case(&self.0))
+    }
+
+    fn rejection(&self) -> StatusCode {
+        StatusCode::UNSUPPORTED_MEDIA_TYPE
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Accept(String);
+
+impl Accept {
+    pub fn new(media_type: &str) -> Self {
+        Self(essence(media_type).to_ascii_lowercase())
+    }
+
+    fn matches(&self, range: &str) -> Option<(u8, f32)> {
+        let mut params = range.split(';');
+        let range = params.next().unwrap_or_default().trim();
+        let quality = params
+            .filter_map(|param| param.split_once('='))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case("q"))
+            .map_or(1.0, |(_, value)| value.trim().parse::<f32>().unwrap_or(0.0));
+
+        let (range_type, range_subtype) = range.split_once('/')?;
+        let (media_type, media_subtype) = self.0.split_once('/').unwrap_or((&self.0, ""));
+
+        let specificity = match (range_type, range_subtype) {
+            ("*", "*") => 0,
+            (range_type, "*") if range_type.eq_ignore_ascii_case(media_type) => 1,
+            (range_type, range_subtype)
+                if range_type.eq_ignore_ascii_case(media_type)
+                    && range_subtype.eq_ignore_ascii_case(media_subtype) =>
+            {
+                2
+            }
+            _ => return None,
+        };
+
+        Some((specificity, quality))
+    }
+
+    fn accepted(&self, parts: &Parts) -> Option<f32> {
+        let mut values = parts.headers.get_all(header::ACCEPT).iter().peekable();
+        values.peek()?;
+
+        let quality = values
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|range| self.matches(range))
+            .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+            .map_or(0.0, |(_, quality)| quality);
+
+        Some(quality)
+    }
+}
+
+impl Guard for Accept {
+    fn check(&self, parts: &Parts) -> bool {
+        self.accepted(parts).is_none_or(|quality| quality > 0.0)
+    }
+
+    fn rejection(&self) -> StatusCode {
+        StatusCode::NOT_ACCEPTABLE
+    }
+
+    fn quality(&self, parts: &Parts) -> Option<f32> {
+        Some(self.accepted(parts).unwrap_or(1.0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Query(String);
+
+impl Query {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl Guard for Query {
+    fn check(&self, parts: &Parts) -> bool {
+        parts.uri.query().is_some_and(|query| {
+            query
+                .split('&')
+                .any(|pair| pair.split('=').next() == Some(self.0.as_str()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header, Request};
+
+    use super::{Accept, Guard};
+
+    fn accepts(guard: &Accept, accept: &str) -> bool {
+        let (parts, _) = Request::get("/")
+            .header(header::ACCEPT, accept)
+            .body(())
+            .unwrap()
+            .into_parts();
+        guard.check(&parts)
+    }
+
+    #[test]
+    fn accept_matches_media_ranges() {
+        let guard = Accept::new("application/json");
+        assert!(accepts(&guard, "text/html, application/json;q=0.5"));
+        assert!(accepts(&guard, "application/*"));
+        assert!(accepts(&guard, "*/*"));
+        assert!(!accepts(&guard, "application/json;q=0, text/html"));
+        assert!(!accepts(&guard, "text/*"));
+
+        let (parts, _) = Request::get("/").body(()).unwrap().into_parts();
+        assert!(guard.check(&parts));
+    }
+
+    #[test]
+    fn accept_quality_uses_the_most_specific_range() {
+        let guard = Accept::new("text/csv");
+        let quality = |accept: &str| {
+            let (parts, _) = Request::get("/")
+                .header(header::ACCEPT, accept)
+                .body(())
+                .unwrap()
+                .into_parts();
+            guard.quality(&parts)
+        };
+
+        assert_eq!(quality("text/csv;q=0.4, text/*;q=0.9"), Some(0.4));
+        assert_eq!(quality("*/*;q=0.1, text/*;q=0.6"), Some(0.6));
+        assert_eq!(quality("text/csv;q=0, */*"), Some(0.0));
+        assert!(!accepts(&guard, "text/csv;q=0, */*"));
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::VecDeque, fmt::Debug, future::Future, marker::PhantomData};
+use std::{collections::VecDeque, fmt::Debug, future::Future, marker::PhantomData, sync::Arc};
 
 use mincat_macro::repeat_macro_max_generics_param;
 
@@ -7,6 +7,7 @@ use crate::{
     next::Next,
     request::{FromRequest, FromRequestParts, Request},
     response::{IntoResponse, Response},
+    state::NoState,
 };
 
 #[derive(Clone)]
@@ -42,7 +43,51 @@ impl Handler {
     }
 
     pub async fn exectue(self, request: Request) -> Response {
-        Next::new(self).run(request).await
+        HandlerChain::from(self).exectue(request).await
+    }
+}
+
+#[derive(Clone)]
+pub struct HandlerChain(Arc<Chain>);
+
+struct Chain {
+    func: Box<dyn HandlerFunc>,
+    middleware: Vec<Box<dyn Middleware>>,
+}
+
+impl Debug for HandlerChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandlerChain")
+            .field("func", &"func")
+            .field("middleware", &self.0.middleware.len())
+            .finish()
+    }
+}
+
+impl From<Handler> for HandlerChain {
+    fn from(handler: Handler) -> Self {
+        Self(Arc::new(Chain {
+            func: handler.func,
+            middleware: handler.middleware.map(Vec::from).unwrap_or_default(),
+        }))
+    }
+}
+
+impl HandlerChain {
+    pub fn middleware_len(&self) -> usize {
+        self.0.middleware.len()
+    }
+
+    pub async fn exectue(&self, request: Request) -> Response {
+        Next::new(self.clone()).run(request).await
+    }
+
+    pub(crate) fn middleware_at(&self, index: usize) -> &dyn Middleware {
+        self.0.middleware[index].as_ref()
+    }
+
+    pub(crate) fn func(&self) -> &dyn HandlerFunc {
+        self.0.func.as_ref()
     }
 }
 
@@ -50,6 +95,12 @@ impl Handler {
 pub trait HandlerFunc: Send + Sync {
     async fn call(self: Box<Self>, request: Request) -> Response;
 
+    /// Runs the handler without taking ownership of it. The default clones
+    /// the handler and calls [`HandlerFunc::call`].
+    async fn call_ref(&self, request: Request) -> Response {
+        self.clone_box().call(request).await
+    }
+
     fn clone_box(&self) -> Box<dyn HandlerFunc>;
 }
 
@@ -108,6 +159,10 @@ where
         self.func.call(request).await
     }
 
+    async fn call_ref(&self, request: Request) -> Response {
+        self.func.clone().call(request).await
+    }
+
     fn clone_box(&self) -> Box<dyn HandlerFunc> {
         Box::new(Self {
             func: self.func.clone(),
@@ -133,14 +188,14 @@ macro_rules! handle_func_param {
     ([$($param: ident),*], $lastparam:ident) => {
         #[allow(non_snake_case)]
         #[async_trait::async_trait]
-        impl<Func, Fut, Res, $($param,)* $lastparam> HandlerFuncParam<($($param,)* $lastparam,)> for Func
+        impl<Func, Fut, Res, M, $($param,)* $lastparam> HandlerFuncParam<(M, $($param,)* $lastparam,)> for Func
         where
             Func: FnOnce($($param,)* $lastparam,) -> Fut,
             Func: Clone + Send + Sync + 'static,
             Fut: Future<Output = Res> + Send,
             Res: IntoResponse,
             $($param: FromRequestParts + Send,)*
-            $lastparam: FromRequest + Send
+            $lastparam: FromRequest<NoState, M> + Send
         {
             async fn call(self, request: Request) -> Response {
                 #[allow(unused_mut)]
@@ -0,0 +1,149 @@
+use std::fmt::Display;
+
+use http::header;
+
+use crate::request::Parts;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum HostLabel {
+    Exact(String),
+    Wildcard,
+    Param(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostPattern {
+    pattern: String,
+    labels: Vec<HostLabel>,
+}
+
+impl HostPattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+        if pattern.is_empty() {
+            return Err("host pattern must not be empty".to_string());
+        }
+
+        let mut labels = vec![];
+        for label in pattern.split('.') {
+            let label = match label {
+                "" => return Err(format!("host pattern `{pattern}` has an empty label")),
+                "*" => HostLabel::Wildcard,
+                label => match label.strip_prefix(':') {
+                    Some("") => {
+                        return Err(format!("host pattern `{pattern}` has an unnamed parameter"))
+                    }
+                    Some(name) => HostLabel::Param(name.to_string()),
+                    None if label.contains([':', '*']) => {
+                        return Err(format!("host pattern `{pattern}` has an invalid label"))
+                    }
+                    None => HostLabel::Exact(label.to_string()),
+                },
+            };
+            labels.push(label);
+        }
+
+        Ok(Self { pattern, labels })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn is_exact(&self) -> bool {
+        self.labels
+            .iter()
+            .all(|label| matches!(label, HostLabel::Exact(_)))
+    }
+
+    pub fn matches<'a, 'p>(&'a self, host: &'p str) -> Option<Vec<(&'a str, &'p str)>> {
+        let host = host.trim_end_matches('.');
+        if host.split('.').count() != self.labels.len() {
+            return None;
+        }
+
+        let mut params = vec![];
+        for (label, value) in self.labels.iter().zip(host.split('.')) {
+            match label {
+                HostLabel::Exact(label) if label.eq_ignore_ascii_case(value) => {}
+                HostLabel::Exact(_) => return None,
+                HostLabel::Wildcard if !value.is_empty() => {}
+                HostLabel::Param(name) if !value.is_empty() => params.push((name.as_str(), value)),
+                _ => return None,
+            }
+        }
+
+        Some(params)
+    }
+}
+
+impl Display for HostPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.pattern)
+    }
+}
+
+pub fn request_host(parts: &Parts) -> Option<&str> {
+    let host = match parts.uri.host() {
+        Some(host) => host,
+        None => parts.headers.get(header::HOST)?.to_str().ok()?,
+    };
+
+    if host.starts_with('[') {
+        return host.find(']').map(|end| &host[..=end]);
+    }
+
+    Some(host.split(':').next().unwrap_or(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header, Request};
+
+    use super::{request_host, HostPattern};
+
+    #[test]
+    fn captures_named_labels() {
+        let pattern = HostPattern::parse(":tenant.Example.com.").unwrap();
+        assert_eq!(pattern.as_str(), ":tenant.example.com");
+        assert!(!pattern.is_exact());
+        assert_eq!(
+            pattern.matches("acme.EXAMPLE.com"),
+            Some(vec![("tenant", "acme")])
+        );
+        assert_eq!(pattern.matches("example.com"), None);
+        assert_eq!(pattern.matches("a.b.example.com"), None);
+    }
+
+    #[test]
+    fn wildcard_matches_one_label() {
+        let pattern = HostPattern::parse("*.example.com").unwrap();
+        assert_eq!(pattern.matches("api.example.com"), Some(vec![]));
+        assert_eq!(pattern.matches(".example.com"), None);
+        assert!(HostPattern::parse("api.example.com").unwrap().is_exact());
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert!(HostPattern::parse("").is_err());
+        assert!(HostPattern::parse("api..com").is_err());
+        assert!(HostPattern::parse(":.example.com").is_err());
+        assert!(HostPattern::parse("a*b.example.com").is_err());
+    }
+
+    #[test]
+    fn reads_host_without_port() {
+        let (parts, _) = Request::get("http://api.example.com:8080/")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert_eq!(request_host(&parts), Some("api.example.com"));
+
+        let (parts, _) = Request::get("/")
+            .header(header::HOST, "[::1]:3000")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert_eq!(request_host(&parts), Some("[::1]"));
+    }
+}
@@ -1,9 +1,16 @@
 pub mod body;
+pub mod constraint;
 pub mod error;
+pub mod guard;
 pub mod handler;
+pub mod host;
 pub mod middleware;
 pub mod next;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod request;
 pub mod response;
 pub mod route;
 pub mod router;
+pub mod state;
+pub mod url_for;
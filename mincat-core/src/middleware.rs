@@ -10,6 +10,13 @@ use crate::{
 pub trait Middleware: Send + Sync {
     async fn call(self: Box<Self>, request: Request, next: Next) -> Response;
 
+    /// Runs the middleware without taking ownership of it, which is how
+    /// [`Next`] walks a frozen chain. The default clones the middleware and
+    /// calls [`Middleware::call`], so override it to avoid that clone.
+    async fn call_ref(&self, request: Request, next: Next) -> Response {
+        self.clone_box().call(request, next).await
+    }
+
     fn clone_box(&self) -> Box<dyn Middleware>;
 }
 
@@ -41,6 +48,10 @@ where
         self.0.call(request, next).await
     }
 
+    async fn call_ref(&self, request: Request, next: Next) -> Response {
+        self.0.clone().call(request, next).await
+    }
+
     fn clone_box(&self) -> Box<dyn Middleware> {
         Box::new(FuncMiddleware(self.0.clone()))
     }
@@ -1,22 +1,31 @@
-use crate::{handler::Handler, request::Request, response::Response};
+use crate::{handler::HandlerChain, request::Request, response::Response};
 
 #[derive(Clone)]
-pub struct Next(Handler);
+pub struct Next {
+    chain: HandlerChain,
+    index: usize,
+}
 
 impl Next {
-    pub fn new(handler: Handler) -> Self {
-        Self(handler)
+    pub fn new<T>(chain: T) -> Self
+    where
+        T: Into<HandlerChain>,
+    {
+        let chain = chain.into();
+        let index = chain.middleware_len();
+        Self { chain, index }
     }
 
     pub async fn run(mut self, request: Request) -> Response {
-        if let Some(arr) = &mut self.0.middleware {
-            if let Some(middleware) = arr.pop_back() {
-                middleware.call(request, self).await
-            } else {
-                self.0.func.call(request).await
-            }
-        } else {
-            self.0.func.call(request).await
+        if self.index == 0 {
+            return self.chain.func().call_ref(request).await;
         }
+
+        self.index -= 1;
+        let chain = self.chain.clone();
+        chain
+            .middleware_at(self.index)
+            .call_ref(request, self)
+            .await
     }
 }
@@ -0,0 +1,463 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+pub use serde_json::{json, Map, Value};
+
+use crate::router::RouteTable;
+
+const ANY_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub trait ToSchema {
+    fn schema() -> Value;
+
+    fn required() -> bool {
+        true
+    }
+}
+
+macro_rules! impl_to_schema {
+    ($schema: tt, $($ty: ty),*) => {
+        $(
+            impl ToSchema for $ty {
+                fn schema() -> Value {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_schema!({ "type": "string" }, String, str, char);
+impl_to_schema!({ "type": "boolean" }, bool);
+impl_to_schema!({ "type": "integer", "format": "int32" }, i8, i16, i32, u8, u16);
+impl_to_schema!({ "type": "integer", "format": "int64" }, i64, u32, u64, isize, usize);
+impl_to_schema!({ "type": "integer" }, i128, u128);
+impl_to_schema!({ "type": "number", "format": "float" }, f32);
+impl_to_schema!({ "type": "number", "format": "double" }, f64);
+impl_to_schema!({}, Value);
+
+impl<T> ToSchema for &T
+where
+    T: ToSchema + ?Sized,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T> ToSchema for Box<T>
+where
+    T: ToSchema + ?Sized,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T> ToSchema for Option<T>
+where
+    T: ToSchema,
+{
+    fn schema() -> Value {
+        T::schema()
+    }
+
+    fn required() -> bool {
+        false
+    }
+}
+
+impl<T> ToSchema for Vec<T>
+where
+    T: ToSchema,
+{
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T> ToSchema for [T]
+where
+    T: ToSchema,
+{
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T> ToSchema for HashMap<String, T>
+where
+    T: ToSchema,
+{
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+impl<T> ToSchema for BTreeMap<String, T>
+where
+    T: ToSchema,
+{
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": T::schema() })
+    }
+}
+
+macro_rules! impl_tuple_to_schema {
+    ($($ty: ident),*) => {
+        impl<$($ty),*> ToSchema for ($($ty,)*)
+        where
+            $($ty: ToSchema,)*
+        {
+            fn schema() -> Value {
+                let items = vec![$($ty::schema()),*];
+                json!({
+                    "type": "array",
+                    "prefixItems": items,
+                    "minItems": items.len(),
+                    "maxItems": items.len(),
+                })
+            }
+        }
+    };
+}
+
+impl_tuple_to_schema!(T1);
+impl_tuple_to_schema!(T1, T2);
+impl_tuple_to_schema!(T1, T2, T3);
+impl_tuple_to_schema!(T1, T2, T3, T4);
+impl_tuple_to_schema!(T1, T2, T3, T4, T5);
+impl_tuple_to_schema!(T1, T2, T3, T4, T5, T6);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Operation {
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub path: Option<Value>,
+    pub query: Option<Value>,
+    pub request_body: Option<(String, Value)>,
+    pub response: Option<(String, Value)>,
+}
+
+impl Operation {
+    pub fn summary<T>(&mut self, summary: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    pub fn description<T>(&mut self, description: T) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn path(&mut self, schema: Value) -> &mut Self {
+        self.path = Some(schema);
+        self
+    }
+
+    pub fn query(&mut self, schema: Value) -> &mut Self {
+        self.query = Some(schema);
+        self
+    }
+
+    pub fn request_body<T>(&mut self, content_type: T, schema: Value) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.request_body = Some((content_type.into(), schema));
+        self
+    }
+
+    pub fn response<T>(&mut self, content_type: T, schema: Value) -> &mut Self
+    where
+        T: Into<String>,
+    {
+        self.response = Some((content_type.into(), schema));
+        self
+    }
+
+    fn to_value(&self, path: &str, name: Option<&str>) -> Value {
+        let mut operation = Map::new();
+        if let Some(summary) = &self.summary {
+            operation.insert("summary".to_string(), json!(summary));
+        }
+
+        if let Some(description) = &self.description {
+            operation.insert("description".to_string(), json!(description));
+        }
+
+        if let Some(name) = name {
+            operation.insert("operationId".to_string(), json!(name));
+        }
+
+        let mut parameters = self.path_parameters(path);
+        parameters.extend(self.query_parameters());
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_string(), Value::Array(parameters));
+        }
+
+        if let Some((content_type, schema)) = &self.request_body {
+            operation.insert(
+                "requestBody".to_string(),
+                json!({
+                    "required": true,
+                    "content": { content_type: { "schema": schema } },
+                }),
+            );
+        }
+
+        let response = match &self.response {
+            Some((content_type, schema)) => json!({
+                "description": "OK",
+                "content": { content_type: { "schema": schema } },
+            }),
+            None => json!({ "description": "OK" }),
+        };
+        operation.insert("responses".to_string(), json!({ "200": response }));
+
+        Value::Object(operation)
+    }
+
+    fn path_parameters(&self, path: &str) -> Vec<Value> {
+        path_params(path)
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let schema = match &self.path {
+                    Some(schema) => param_schema(schema, name, index),
+                    None => json!({ "type": "string" }),
+                };
+
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": schema,
+                })
+            })
+            .collect()
+    }
+
+    fn query_parameters(&self) -> Vec<Value> {
+        let Some(schema) = &self.query else {
+            return vec![];
+        };
+
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            return vec![];
+        };
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        properties
+            .iter()
+            .map(|(name, schema)| {
+                json!({
+                    "name": name,
+                    "in": "query",
+                    "required": required.contains(&json!(name)),
+                    "schema": schema,
+                })
+            })
+            .collect()
+    }
+}
+
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| segment.find([':', '*']).map(|start| &segment[start + 1..]))
+        .collect()
+}
+
+fn param_schema(schema: &Value, name: &str, index: usize) -> Value {
+    if let Some(schema) = schema.get("properties").and_then(|value| value.get(name)) {
+        return schema.clone();
+    }
+
+    if let Some(schema) = schema.get("prefixItems").and_then(|value| value.get(index)) {
+        return schema.clone();
+    }
+
+    match schema.get("type").and_then(Value::as_str) {
+        Some("object") | Some("array") => json!({ "type": "string" }),
+        _ => schema.clone(),
+    }
+}
+
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.find([':', '*']) {
+            Some(start) => format!("{}{{{}}}", &segment[..start], &segment[start + 1..]),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub trait OperationInput {
+    fn operation(operation: &mut Operation);
+}
+
+pub trait OperationOutput {
+    fn operation(operation: &mut Operation);
+}
+
+impl<T, E> OperationOutput for Result<T, E>
+where
+    T: OperationOutput,
+{
+    fn operation(operation: &mut Operation) {
+        T::operation(operation);
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use std::marker::PhantomData;
+
+    use super::{Operation, OperationInput, OperationOutput};
+
+    pub struct Describe<T>(PhantomData<T>);
+
+    impl<T> Describe<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait DescribeInput {
+        fn describe_input(&self, operation: &mut Operation);
+    }
+
+    impl<T> DescribeInput for &Describe<T>
+    where
+        T: OperationInput,
+    {
+        fn describe_input(&self, operation: &mut Operation) {
+            T::operation(operation);
+        }
+    }
+
+    pub trait DescribeInputFallback {
+        fn describe_input(&self, _: &mut Operation) {}
+    }
+
+    impl<T> DescribeInputFallback for Describe<T> {}
+
+    pub trait DescribeOutput {
+        fn describe_output(&self, operation: &mut Operation);
+    }
+
+    impl<T> DescribeOutput for &Describe<T>
+    where
+        T: OperationOutput,
+    {
+        fn describe_output(&self, operation: &mut Operation) {
+            T::operation(operation);
+        }
+    }
+
+    pub trait DescribeOutputFallback {
+        fn describe_output(&self, _: &mut Operation) {}
+    }
+
+    impl<T> DescribeOutputFallback for Describe<T> {}
+}
+
+#[derive(Clone, Debug)]
+pub struct OpenApi {
+    title: String,
+    version: String,
+    host: Option<String>,
+    routes: RouteTable,
+}
+
+impl OpenApi {
+    pub fn new(routes: RouteTable) -> Self {
+        Self {
+            title: "mincat".to_string(),
+            version: "0.1.0".to_string(),
+            host: None,
+            routes,
+        }
+    }
+
+    pub fn title<T>(mut self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.title = title.into();
+        self
+    }
+
+    pub fn version<T>(mut self, version: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.version = version.into();
+        self
+    }
+
+    /// Documents the routes of the host router registered under `host`.
+    ///
+    /// A document only covers one host: by default the routes outside any
+    /// host router, since host routers may reuse the same paths.
+    pub fn host<T>(mut self, host: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.host = Some(host.into());
+        self
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut paths = BTreeMap::<String, Map<String, Value>>::new();
+        for route in &self.routes {
+            if route.host != self.host {
+                continue;
+            }
+
+            let operation = route.operation.to_value(&route.path, route.name.as_deref());
+            let path = paths.entry(openapi_path(&route.path)).or_default();
+            match &route.method {
+                Some(method) => {
+                    path.insert(method.as_str().to_lowercase(), operation);
+                }
+                None => {
+                    for method in ANY_METHODS {
+                        path.entry(method.to_string())
+                            .or_insert_with(|| operation.clone());
+                    }
+                }
+            }
+        }
+
+        json!({
+            "openapi": "3.1.0",
+            "info": { "title": self.title, "version": self.version },
+            "paths": paths,
+        })
+    }
+}
+
+impl Display for OpenApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = serde_json::to_string_pretty(&self.to_value()).map_err(|_| std::fmt::Error)?;
+        write!(f, "{value}")
+    }
+}
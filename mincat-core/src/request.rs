@@ -1,6 +1,11 @@
+use std::convert::Infallible;
+
+use http::Method;
+
 use crate::{
     body::{Body, BodyLimitedSize},
     response::IntoResponse,
+    state::{AppState, NoState},
 };
 
 pub use http::request::Parts;
@@ -21,19 +26,39 @@ impl RequestExt for Request {
     }
 }
 
+/// Marks the [`FromRequest`] impl of an extractor that reads the whole request.
+pub enum ViaRequest {}
+
+/// Marks the [`FromRequest`] impl every [`FromRequestParts`] extractor gets.
+pub enum ViaParts {}
+
+/// Extracts a value from the whole request.
+///
+/// `S` is the state type of the router the handler is added to. Extractors
+/// that work on any router implement the trait for every `S`. `M` only keeps
+/// the impl for [`FromRequestParts`] extractors apart from the others.
 #[async_trait::async_trait]
-pub trait FromRequest: Sized {
+pub trait FromRequest<S = NoState, M = ViaRequest>: Sized {
     type Error: IntoResponse;
 
+    /// Adds the values this extractor reads to the state of its route.
+    ///
+    /// Called once per route when the router is frozen.
+    fn provide_state(_state: &S, _route_state: &mut AppState) {}
+
     async fn from_request(request: Request) -> Result<Self, Self::Error>;
 }
 
 #[async_trait::async_trait]
-impl<T> FromRequest for T
+impl<S, T> FromRequest<S, ViaParts> for T
 where
-    T: FromRequestParts,
+    T: FromRequestParts<S>,
 {
-    type Error = <Self as FromRequestParts>::Error;
+    type Error = <Self as FromRequestParts<S>>::Error;
+
+    fn provide_state(state: &S, route_state: &mut AppState) {
+        <Self as FromRequestParts<S>>::provide_state(state, route_state);
+    }
 
     async fn from_request(req: Request) -> Result<Self, Self::Error> {
         let (mut parts, _) = req.into_parts();
@@ -41,9 +66,26 @@ where
     }
 }
 
+/// Extracts a value from the request head.
+///
+/// See [`FromRequest`] for the meaning of `S`.
 #[async_trait::async_trait]
-pub trait FromRequestParts: Sized {
+pub trait FromRequestParts<S = NoState>: Sized {
     type Error: IntoResponse;
 
+    /// Adds the values this extractor reads to the state of its route.
+    ///
+    /// Called once per route when the router is frozen.
+    fn provide_state(_state: &S, _route_state: &mut AppState) {}
+
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error>;
 }
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for Method {
+    type Error = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        Ok(parts.method.clone())
+    }
+}
@@ -1,15 +1,63 @@
 use http::Method;
 
+#[cfg(feature = "openapi")]
+use crate::openapi::Operation;
 use crate::{
+    constraint::PathConstraints,
+    guard::{Guard, Guards},
     handler::{FuncParamHandler, Handler, HandlerFuncParam},
     middleware::Middleware,
+    state::{AppState, NoState},
 };
 
-#[derive(Clone)]
+pub type PathCheck = fn(&[&str]) -> Result<(), String>;
+
+/// Adds the values a route reads from the app state to its route state.
+pub type StateProvider = fn(&AppState, &mut AppState);
+
+pub trait PathParams {
+    fn check_params(params: &[&str]) -> Result<(), String>;
+}
+
+/// A field of a struct extracted with `Path`, under its serde name.
+#[derive(Clone, Copy, Debug)]
+pub struct PathField {
+    pub name: &'static str,
+    pub required: bool,
+    /// The compile error when the field is required but not a route parameter.
+    pub unmatched: &'static str,
+}
+
+/// The fields a `Path<T>` reads, which the route macros compare with their
+/// pattern at compile time. Derive it with `#[derive(PathFields)]`.
+pub trait PathFields {
+    /// `None` when any parameters are accepted, as for maps.
+    const FIELDS: Option<&'static [PathField]>;
+
+    /// Whether parameters that are not fields are rejected.
+    const DENY_UNKNOWN: bool = false;
+}
+
+impl<K, V, H> PathFields for std::collections::HashMap<K, V, H> {
+    const FIELDS: Option<&'static [PathField]> = None;
+}
+
+impl<K, V> PathFields for std::collections::BTreeMap<K, V> {
+    const FIELDS: Option<&'static [PathField]> = None;
+}
+
+#[derive(Clone, Debug)]
 pub struct Route {
-    pub method: Method,
+    pub method: Option<Method>,
     pub path: String,
     pub handler: Handler,
+    pub name: Option<String>,
+    pub guards: Guards,
+    pub constraints: PathConstraints,
+    pub path_checks: Vec<PathCheck>,
+    pub state_providers: Vec<StateProvider>,
+    #[cfg(feature = "openapi")]
+    pub operation: Operation,
 }
 
 impl Route {
@@ -21,7 +69,55 @@ impl Route {
         self.clone()
     }
 
+    pub fn name<T>(&mut self, name: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.name = Some(name.into());
+        self.clone()
+    }
+
+    pub fn guard<G>(&mut self, guard: G) -> Self
+    where
+        G: Guard,
+    {
+        self.guards.push(guard);
+        self.clone()
+    }
+
+    pub fn path_check(&mut self, check: PathCheck) -> Self {
+        self.path_checks.push(check);
+        self.clone()
+    }
+
+    #[cfg(feature = "openapi")]
+    pub fn operation<F>(&mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut Operation),
+    {
+        f(&mut self.operation);
+        self.clone()
+    }
+
     pub fn init<Path, Func, Param>(method: Method, path: Path, func: Func) -> Self
+    where
+        Path: Into<String>,
+        Func: HandlerFuncParam<Param> + Sync + Clone + 'static,
+        Param: Send + Sync + 'static,
+    {
+        Self::with_method(Some(method), path, func)
+    }
+
+    pub fn any<Path, Func, Param>(path: Path, func: Func) -> Self
+    where
+        Path: Into<String>,
+        Func: HandlerFuncParam<Param> + Sync + Clone + 'static,
+        Param: Send + Sync + 'static,
+    {
+        Self::with_method(None, path, func)
+    }
+
+    fn with_method<Path, Func, Param>(method: Option<Method>, path: Path, func: Func) -> Self
     where
         Path: Into<String>,
         Func: HandlerFuncParam<Param> + Sync + Clone + 'static,
@@ -33,16 +129,233 @@ impl Route {
             method,
             path,
             handler,
+            name: None,
+            guards: Guards::default(),
+            constraints: PathConstraints::default(),
+            path_checks: vec![],
+            state_providers: vec![],
+            #[cfg(feature = "openapi")]
+            operation: Operation::default(),
         }
     }
+
+    pub fn provide_state(&mut self, provider: StateProvider) -> Self {
+        self.state_providers.push(provider);
+        self.clone()
+    }
 }
 
 impl From<(Method, String, Handler)> for Route {
     fn from(value: (Method, String, Handler)) -> Self {
+        Self::from((Some(value.0), value.1, value.2))
+    }
+}
+
+impl From<(Option<Method>, String, Handler)> for Route {
+    fn from(value: (Option<Method>, String, Handler)) -> Self {
         Self {
             method: value.0,
             path: value.1,
             handler: value.2,
+            name: None,
+            guards: Guards::default(),
+            constraints: PathConstraints::default(),
+            path_checks: vec![],
+            state_providers: vec![],
+            #[cfg(feature = "openapi")]
+            operation: Operation::default(),
+        }
+    }
+}
+
+/// Converts a value into the routes of a `Router<S>`.
+///
+/// `M` is inferred. Handlers use it to name the [`FromRequest`] impl of their
+/// last argument.
+///
+/// [`FromRequest`]: crate::request::FromRequest
+pub trait IntoRoute<S = NoState, M = ()> {
+    fn into_routes(self) -> Vec<Route>;
+}
+
+/// A `Route` keeps the state providers it was built with. One converted with
+/// `Route::from(handler)` is untyped, so on a typed router its `State`
+/// extractors read the state itself and values added with `App::state`,
+/// but no `FromRef` sub-states. Use `IntoRoute::<S, _>::into_routes(handler)`
+/// to build routes that resolve sub-states of `S`.
+impl<S> IntoRoute<S> for Route {
+    fn into_routes(self) -> Vec<Route> {
+        vec![self]
+    }
+}
+
+impl<S> IntoRoute<S> for Vec<Route> {
+    fn into_routes(self) -> Vec<Route> {
+        self
+    }
+}
+
+#[derive(Clone)]
+pub struct WithMiddleware<R> {
+    route: R,
+    middleware: Vec<Box<dyn Middleware>>,
+}
+
+impl<R> WithMiddleware<R>
+where
+    R: Clone,
+{
+    pub fn new(route: R) -> Self {
+        Self {
+            route,
+            middleware: vec![],
+        }
+    }
+
+    pub fn middleware<T>(&mut self, middleware: T) -> Self
+    where
+        T: Into<Box<dyn Middleware>>,
+    {
+        self.middleware.push(middleware.into());
+        self.clone()
+    }
+}
+
+impl<S, M, R> IntoRoute<S, M> for WithMiddleware<R>
+where
+    R: IntoRoute<S, M>,
+{
+    fn into_routes(self) -> Vec<Route> {
+        let mut routes = self.route.into_routes();
+        for route in &mut routes {
+            for middleware in &self.middleware {
+                route.middleware(middleware.clone());
+            }
+        }
+
+        routes
+    }
+}
+
+impl<R> From<WithMiddleware<R>> for Route
+where
+    R: Into<Route>,
+{
+    fn from(value: WithMiddleware<R>) -> Self {
+        let mut route = value.route.into();
+        for middleware in value.middleware {
+            route.middleware(middleware);
+        }
+
+        route
+    }
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use std::marker::PhantomData;
+
+    use super::{PathCheck, PathField, PathFields, PathParams};
+
+    pub struct CheckPath<T>(PhantomData<T>);
+
+    impl<T> CheckPath<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait CheckPathParams {
+        fn path_check(&self) -> Option<PathCheck>;
+    }
+
+    impl<T> CheckPathParams for &CheckPath<T>
+    where
+        T: PathParams,
+    {
+        fn path_check(&self) -> Option<PathCheck> {
+            Some(T::check_params)
+        }
+    }
+
+    pub trait CheckPathParamsFallback {
+        fn path_check(&self) -> Option<PathCheck> {
+            None
+        }
+    }
+
+    impl<T> CheckPathParamsFallback for CheckPath<T> {}
+
+    const fn str_eq(a: &str, b: &str) -> bool {
+        let (a, b) = (a.as_bytes(), b.as_bytes());
+        if a.len() != b.len() {
+            return false;
+        }
+
+        let mut i = 0;
+        while i < a.len() {
+            if a[i] != b[i] {
+                return false;
+            }
+            i += 1;
+        }
+
+        true
+    }
+
+    const fn is_param(params: &[&str], name: &str) -> bool {
+        let mut i = 0;
+        while i < params.len() {
+            if str_eq(params[i], name) {
+                return true;
+            }
+            i += 1;
+        }
+
+        false
+    }
+
+    const fn is_field(fields: &[PathField], name: &str) -> bool {
+        let mut i = 0;
+        while i < fields.len() {
+            if str_eq(fields[i].name, name) {
+                return true;
+            }
+            i += 1;
+        }
+
+        false
+    }
+
+    /// Panics at compile time when `T` does not match the route parameters.
+    /// `unknown[i]` is the error for `params[i]` not being a field.
+    pub const fn check_path_fields<T>(params: &[&str], unknown: &[&str])
+    where
+        T: PathFields + ?Sized,
+    {
+        let Some(fields) = T::FIELDS else {
+            return;
+        };
+
+        let mut i = 0;
+        while i < fields.len() {
+            if fields[i].required && !is_param(params, fields[i].name) {
+                panic!("{}", fields[i].unmatched);
+            }
+            i += 1;
+        }
+
+        if !T::DENY_UNKNOWN {
+            return;
+        }
+
+        let mut i = 0;
+        while i < params.len() {
+            if !is_field(fields, params[i]) {
+                panic!("{}", unknown[i]);
+            }
+            i += 1;
         }
     }
 }
@@ -1,13 +1,94 @@
-use std::{borrow::BorrowMut, collections::HashMap, fmt::Debug};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    fmt::{Debug, Display},
+    marker::PhantomData,
+    sync::Arc,
+};
 
-use http::Method;
+use http::{header, HeaderValue, Method, StatusCode};
+use matchit::{InsertError, Params};
+use mincat_pattern::param_names;
 
-use crate::{handler::Handler, middleware::Middleware, route::Route};
+#[cfg(feature = "openapi")]
+use crate::openapi::{OpenApi, Operation};
+use crate::{
+    constraint::{normalize_pattern, PathConstraints},
+    guard::Guards,
+    handler::{FuncParamHandler, Handler, HandlerChain, HandlerFuncParam},
+    host::{request_host, HostPattern},
+    middleware::Middleware,
+    request::Parts,
+    route::{IntoRoute, PathCheck, Route},
+    state::{AppState, NoState, RouteState},
+    url_for::{RouteNames, UrlForError},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RouteError {
+    NotFound,
+    MethodNotAllowed(Vec<Method>),
+    Rejected(StatusCode),
+}
+
+impl RouteError {
+    pub fn allow(&self) -> Option<HeaderValue> {
+        match self {
+            RouteError::MethodNotAllowed(methods) => Some(allow_header(methods)),
+            RouteError::NotFound | RouteError::Rejected(_) => None,
+        }
+    }
+
+    fn rejected(status: StatusCode) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => RouteError::NotFound,
+            status => RouteError::Rejected(status),
+        }
+    }
+}
+
+fn allow_header(methods: &[Method]) -> HeaderValue {
+    let methods = methods
+        .iter()
+        .map(Method::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    HeaderValue::from_str(&methods).expect("method is a valid header value")
+}
+
+fn select_guarded<'a, T>(
+    candidates: impl IntoIterator<Item = &'a T>,
+    check: impl Fn(&T) -> Result<Option<f32>, StatusCode>,
+) -> Option<Result<&'a T, RouteError>> {
+    let mut selected: Option<(&T, Option<f32>)> = None;
+    let mut rejection = None;
+    for candidate in candidates {
+        match (check(candidate), selected) {
+            (Ok(quality), None) => selected = Some((candidate, quality)),
+            (Ok(Some(quality)), Some((_, Some(best)))) if quality > best => {
+                selected = Some((candidate, Some(quality)))
+            }
+            (Ok(_), Some(_)) => {}
+            (Err(status), _) => {
+                rejection.get_or_insert(status);
+            }
+        }
+    }
+
+    match (selected, rejection) {
+        (Some((candidate, _)), _) => Some(Ok(candidate)),
+        (None, Some(status)) => Some(Err(RouteError::rejected(status))),
+        (None, None) => None,
+    }
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct Endpoint {
     path: String,
-    method_handler: HashMap<Method, Handler>,
+    method_routes: HashMap<Option<Method>, Vec<Route>>,
+    disable_auto_head: bool,
+    disable_auto_options: bool,
 }
 
 impl Endpoint {
@@ -20,24 +101,588 @@ impl Endpoint {
         self.clone()
     }
 
-    fn method_handler(&mut self, method: &Method, handler: &Handler) -> Self {
-        self.method_handler.insert(method.clone(), handler.clone());
+    fn method_route(&mut self, route: Route) -> Self {
+        let routes = self.method_routes.entry(route.method.clone()).or_default();
+        let is_unguarded = |route: &Route| route.guards.is_empty() && route.constraints.is_empty();
+        let unguarded = routes.iter().position(is_unguarded);
+        match (is_unguarded(&route), unguarded) {
+            (true, Some(index)) => routes[index] = route,
+            (false, Some(index)) => routes.insert(index, route),
+            (_, None) => routes.push(route),
+        }
         self.clone()
     }
+
+    fn routes(&self) -> impl Iterator<Item = &Route> {
+        self.method_routes.values().flatten()
+    }
+
+    fn constraints(&self, prefix: &str) -> Vec<FrozenPattern> {
+        if self.routes().any(|route| route.constraints.is_empty()) {
+            return vec![];
+        }
+
+        self.routes()
+            .map(|route| FrozenPattern::new(prefix, &route.path, &route.constraints))
+            .collect()
+    }
+
+    fn get(&self, method: Method) -> Option<&Vec<Route>> {
+        self.method_routes.get(&Some(method))
+    }
+
+    fn allow_methods(&self, auto_head: bool, auto_options: bool) -> Vec<Method> {
+        let mut methods = self
+            .method_routes
+            .keys()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+        if auto_head && self.get(Method::GET).is_some() && self.get(Method::HEAD).is_none() {
+            methods.push(Method::HEAD);
+        }
+
+        if auto_options && self.get(Method::OPTIONS).is_none() {
+            methods.push(Method::OPTIONS);
+        }
+
+        methods.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        methods
+    }
+
+    fn options_handler(&self, methods: &[Method]) -> Handler {
+        let allow = allow_header(methods);
+        let mut handler: Handler = FuncParamHandler::from(move || {
+            let allow = allow.clone();
+            async move { ([(header::ALLOW, allow)], ()) }
+        })
+        .into();
+
+        let template = self.get(Method::GET).or_else(|| {
+            self.method_routes
+                .iter()
+                .filter_map(|(method, routes)| Some((method.as_ref()?, routes)))
+                .min_by(|a, b| a.0.as_str().cmp(b.0.as_str()))
+                .map(|(_, routes)| routes)
+        });
+
+        if let Some(template) = template.and_then(|routes| routes.first()) {
+            handler.middleware = template.handler.middleware.clone();
+        }
+
+        handler
+    }
+
+    fn freeze<S>(&self, router: &Router<S>, prefix: &str, state: &AppState) -> FrozenEndpoint {
+        let auto_head = !router.disable_auto_head && !self.disable_auto_head;
+        let auto_options = !router.disable_auto_options && !self.disable_auto_options;
+        let allow = self.allow_methods(auto_head, auto_options);
+        let freeze_routes = |routes: &Vec<Route>| {
+            routes
+                .iter()
+                .map(|route| {
+                    let pattern = FrozenPattern::new(prefix, &route.path, &route.constraints);
+                    pattern.check_extractors(&route.path_checks);
+                    let mut route_state = AppState::default();
+                    for provide in &route.state_providers {
+                        provide(state, &mut route_state);
+                    }
+
+                    FrozenHandler {
+                        chain: router.apply_middleware(route.handler.clone()).into(),
+                        guards: route.guards.clone(),
+                        pattern,
+                        state: (!route_state.is_empty()).then_some(RouteState(route_state)),
+                    }
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut method_chain = self
+            .method_routes
+            .iter()
+            .filter_map(|(method, routes)| Some((method.clone()?, freeze_routes(routes))))
+            .collect::<HashMap<_, _>>();
+        let any_chain = self
+            .method_routes
+            .get(&None)
+            .map(freeze_routes)
+            .unwrap_or_default();
+
+        if auto_head && !method_chain.contains_key(&Method::HEAD) {
+            if let Some(chain) = method_chain.get(&Method::GET).cloned() {
+                method_chain.insert(Method::HEAD, chain);
+            }
+        }
+
+        if auto_options && any_chain.is_empty() && !method_chain.contains_key(&Method::OPTIONS) {
+            let handler = FrozenHandler {
+                chain: router.apply_middleware(self.options_handler(&allow)).into(),
+                guards: Guards::default(),
+                pattern: FrozenPattern::new(prefix, &self.path, &PathConstraints::default()),
+                state: None,
+            };
+            method_chain.insert(Method::OPTIONS, vec![handler]);
+        }
+
+        FrozenEndpoint {
+            method_chain,
+            any_chain,
+            allow,
+            constraints: self.constraints(prefix),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FrozenPattern {
+    path: Arc<str>,
+    params: Vec<String>,
+    constraints: PathConstraints,
+}
+
+impl FrozenPattern {
+    fn new(prefix: &str, path: &str, constraints: &PathConstraints) -> Self {
+        Self {
+            path: join_path(prefix, path).into(),
+            params: param_names(path).into_iter().map(str::to_string).collect(),
+            constraints: constraints.clone(),
+        }
+    }
+
+    fn bind<'a, 'p>(&'a self, params: &Params<'_, 'p>) -> Vec<(&'a str, &'p str)> {
+        self.params
+            .iter()
+            .map(String::as_str)
+            .zip(params.iter().map(|(_, value)| value))
+            .collect()
+    }
+
+    fn check(&self, params: &Params) -> bool {
+        self.constraints.check(&self.bind(params))
+    }
+
+    fn check_extractors(&self, checks: &[PathCheck]) {
+        let params = self.params.iter().map(String::as_str).collect::<Vec<_>>();
+        for check in checks {
+            if let Err(e) = check(&params) {
+                panic!("invalid Path extractor for route `{}`: {e}", self.path);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FrozenHandler {
+    chain: HandlerChain,
+    guards: Guards,
+    pattern: FrozenPattern,
+    state: Option<RouteState>,
+}
+
+impl FrozenHandler {
+    fn check(&self, params: &Params, parts: &Parts) -> Result<Option<f32>, StatusCode> {
+        if !self.pattern.check(params) {
+            return Err(StatusCode::NOT_FOUND);
+        }
+
+        self.guards.check(parts)?;
+        Ok(self.guards.quality(parts))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct FrozenEndpoint {
+    method_chain: HashMap<Method, Vec<FrozenHandler>>,
+    any_chain: Vec<FrozenHandler>,
+    allow: Vec<Method>,
+    constraints: Vec<FrozenPattern>,
+}
+
+fn join_path(prefix: &str, path: &str) -> String {
+    match (prefix, path) {
+        ("", path) => path.to_string(),
+        (prefix, "/") => prefix.to_string(),
+        (prefix, path) => format!("{prefix}{path}"),
+    }
+}
+
+fn strip_nest_prefix<'p>(path: &'p str, prefix: &str) -> Option<&'p str> {
+    let rest = path.strip_prefix(prefix)?;
+    if rest.is_empty() {
+        Some("/")
+    } else if rest.starts_with('/') {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+pub struct RouteMatch<'a, 'p> {
+    pub path: &'a Arc<str>,
+    pub chain: &'a HandlerChain,
+    pub params: Vec<(&'a str, &'p str)>,
+    pub host_params: Vec<(&'a str, &'p str)>,
+    pub nested_path: Option<&'a Arc<str>>,
+    pub state: Option<&'a RouteState>,
+}
+
+pub struct RouteMiss<'a> {
+    pub error: RouteError,
+    pub fallback: Option<&'a HandlerChain>,
+    pub nested_path: Option<&'a Arc<str>>,
 }
 
 #[derive(Clone, Default)]
-pub struct Router {
+pub struct FrozenRouter {
+    path_index: matchit::Router<usize>,
+    // One router per pattern, retried in order when a constraint rejects the best match.
+    fallthrough: Vec<(matchit::Router<usize>, usize)>,
+    shadowed: bool,
+    index_endpoint: HashMap<usize, FrozenEndpoint>,
+    fallback: Option<HandlerChain>,
+    method_not_allowed_fallback: Option<HandlerChain>,
+    nested: Vec<(String, FrozenRouter)>,
+    nested_path: Option<Arc<str>>,
+    hosts: Vec<(HostPattern, FrozenRouter)>,
+    names: Arc<RouteNames>,
+}
+
+impl Debug for FrozenRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FrozenRouter")
+            .field("index_endpoint", &self.index_endpoint)
+            .field("fallback", &self.fallback)
+            .field(
+                "method_not_allowed_fallback",
+                &self.method_not_allowed_fallback,
+            )
+            .field("nested", &self.nested)
+            .field("nested_path", &self.nested_path)
+            .field("hosts", &self.hosts)
+            .field("names", &self.names)
+            .finish()
+    }
+}
+
+impl FrozenRouter {
+    pub fn names(&self) -> &Arc<RouteNames> {
+        &self.names
+    }
+
+    pub fn get_handler<'a, 'p>(
+        &'a self,
+        parts: &'p Parts,
+    ) -> Result<RouteMatch<'a, 'p>, RouteMiss<'a>> {
+        self.get_handler_at(parts, parts.uri.path())
+    }
+
+    fn get_handler_at<'a, 'p>(
+        &'a self,
+        parts: &'p Parts,
+        path: &'p str,
+    ) -> Result<RouteMatch<'a, 'p>, RouteMiss<'a>> {
+        if let Some(host) = request_host(parts) {
+            for (pattern, router) in &self.hosts {
+                let Some(host_params) = pattern.matches(host) else {
+                    continue;
+                };
+
+                return router
+                    .get_handler_at(parts, path)
+                    .map(|mut matched| {
+                        matched.host_params.splice(0..0, host_params);
+                        matched
+                    })
+                    .map_err(|miss| {
+                        if miss.fallback.is_some() {
+                            miss
+                        } else {
+                            self.route_miss(miss.error)
+                        }
+                    });
+            }
+        }
+
+        let error = match self.find_handler(parts, path) {
+            Ok(matched) => return Ok(matched),
+            Err(error) => error,
+        };
+
+        if error == RouteError::NotFound {
+            for (prefix, router) in &self.nested {
+                let Some(path) = strip_nest_prefix(path, prefix) else {
+                    continue;
+                };
+
+                return router.get_handler_at(parts, path).map_err(|miss| {
+                    if miss.fallback.is_some() {
+                        miss
+                    } else {
+                        self.route_miss(miss.error)
+                    }
+                });
+            }
+        }
+
+        Err(self.route_miss(error))
+    }
+
+    fn find_handler<'a, 'p>(
+        &'a self,
+        parts: &Parts,
+        path: &'p str,
+    ) -> Result<RouteMatch<'a, 'p>, RouteError> {
+        let mut rejected = None;
+        if !self.shadowed {
+            let matched = self.path_index.at(path).map_err(|_| RouteError::NotFound)?;
+            match self.match_endpoint(*matched.value, &matched.params, parts) {
+                Err(RouteError::NotFound) => rejected = Some(*matched.value),
+                result => return result,
+            }
+        }
+
+        for (patterns, index) in &self.fallthrough {
+            if rejected == Some(*index) {
+                continue;
+            }
+
+            let Ok(matched) = patterns.at(path) else {
+                continue;
+            };
+
+            match self.match_endpoint(*matched.value, &matched.params, parts) {
+                Err(RouteError::NotFound) => continue,
+                result => return result,
+            }
+        }
+
+        Err(RouteError::NotFound)
+    }
+
+    fn match_endpoint<'a, 'p>(
+        &'a self,
+        index: usize,
+        params: &Params<'_, 'p>,
+        parts: &Parts,
+    ) -> Result<RouteMatch<'a, 'p>, RouteError> {
+        let endpoint = self
+            .index_endpoint
+            .get(&index)
+            .ok_or(RouteError::NotFound)?;
+
+        let constrained = !endpoint.constraints.is_empty();
+        if constrained
+            && !endpoint
+                .constraints
+                .iter()
+                .any(|pattern| pattern.check(params))
+        {
+            return Err(RouteError::NotFound);
+        }
+
+        let handlers = endpoint
+            .method_chain
+            .get(&parts.method)
+            .into_iter()
+            .flatten()
+            .chain(&endpoint.any_chain);
+
+        match select_guarded(handlers, |handler| handler.check(params, parts)) {
+            Some(Ok(handler)) => Ok(RouteMatch {
+                path: &handler.pattern.path,
+                chain: &handler.chain,
+                params: handler.pattern.bind(params),
+                host_params: vec![],
+                nested_path: self.nested_path.as_ref(),
+                state: handler.state.as_ref(),
+            }),
+            Some(Err(error)) => Err(error),
+            None => Err(RouteError::MethodNotAllowed(endpoint.allow.clone())),
+        }
+    }
+
+    fn route_miss(&self, error: RouteError) -> RouteMiss<'_> {
+        let fallback = match error {
+            RouteError::NotFound => self.fallback.as_ref(),
+            RouteError::MethodNotAllowed(_) => self.method_not_allowed_fallback.as_ref(),
+            RouteError::Rejected(_) => None,
+        };
+
+        RouteMiss {
+            error,
+            fallback,
+            nested_path: self.nested_path.as_ref(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteInfo {
+    pub host: Option<String>,
+    pub method: Option<Method>,
+    pub path: String,
+    pub name: Option<String>,
+    pub constraints: PathConstraints,
+    pub middleware: usize,
+    #[cfg(feature = "openapi")]
+    pub operation: Operation,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RouteTable(Vec<RouteInfo>);
+
+impl RouteTable {
+    pub fn iter(&self) -> std::slice::Iter<'_, RouteInfo> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl IntoIterator for RouteTable {
+    type Item = RouteInfo;
+    type IntoIter = std::vec::IntoIter<RouteInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a RouteTable {
+    type Item = &'a RouteInfo;
+    type IntoIter = std::slice::Iter<'a, RouteInfo>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl Display for RouteTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let with_host = self.0.iter().any(|route| route.host.is_some());
+        let header = ["HOST", "METHOD", "PATH", "NAME", "MIDDLEWARE"];
+        let rows = self
+            .0
+            .iter()
+            .map(|route| {
+                [
+                    route.host.clone().unwrap_or_else(|| "-".to_string()),
+                    route
+                        .method
+                        .as_ref()
+                        .map_or("ANY".to_string(), Method::to_string),
+                    route.constraints.pattern(&route.path),
+                    route.name.clone().unwrap_or_else(|| "-".to_string()),
+                    route.middleware.to_string(),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let header = header.map(str::to_string);
+        let skip = usize::from(!with_host);
+        for row in std::iter::once(&header).chain(&rows) {
+            let line = row
+                .iter()
+                .zip(widths)
+                .skip(skip)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Router<S = NoState> {
     index: usize,
     index_endpoint: HashMap<usize, Endpoint>,
     path_index: matchit::Router<usize>,
+    shadowed: bool,
+    pattern_index: HashMap<String, usize>,
+    fallback: Option<Handler>,
+    method_not_allowed_fallback: Option<Handler>,
+    disable_auto_head: bool,
+    disable_auto_options: bool,
+    middleware: VecDeque<Box<dyn Middleware>>,
+    nested: Vec<(String, Router<S>)>,
+    hosts: Vec<(HostPattern, Router<S>)>,
+    names: RouteNames,
+    _state: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for Router<S> {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index,
+            index_endpoint: self.index_endpoint.clone(),
+            path_index: self.path_index.clone(),
+            shadowed: self.shadowed,
+            pattern_index: self.pattern_index.clone(),
+            fallback: self.fallback.clone(),
+            method_not_allowed_fallback: self.method_not_allowed_fallback.clone(),
+            disable_auto_head: self.disable_auto_head,
+            disable_auto_options: self.disable_auto_options,
+            middleware: self.middleware.clone(),
+            nested: self.nested.clone(),
+            hosts: self.hosts.clone(),
+            names: self.names.clone(),
+            _state: PhantomData,
+        }
+    }
 }
 
-impl Debug for Router {
+impl<S> Default for Router<S> {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            index_endpoint: HashMap::new(),
+            path_index: matchit::Router::new(),
+            shadowed: false,
+            pattern_index: HashMap::new(),
+            fallback: None,
+            method_not_allowed_fallback: None,
+            disable_auto_head: false,
+            disable_auto_options: false,
+            middleware: VecDeque::new(),
+            nested: vec![],
+            hosts: vec![],
+            names: RouteNames::default(),
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<S> Debug for Router<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Router")
             .field("index", &self.index)
             .field("index_endpoint", &self.index_endpoint)
+            .field("fallback", &self.fallback)
+            .field(
+                "method_not_allowed_fallback",
+                &self.method_not_allowed_fallback,
+            )
+            .field("auto_head", &!self.disable_auto_head)
+            .field("auto_options", &!self.disable_auto_options)
+            .field("middleware", &self.middleware.len())
+            .field("nested", &self.nested)
+            .field("hosts", &self.hosts)
+            .field("names", &self.names)
             .finish()
     }
 }
@@ -46,84 +691,611 @@ impl Router {
     pub fn new() -> Self {
         Self::default()
     }
+}
 
-    pub fn group(&mut self, path: &str, router: Router) -> Self {
+impl<S> Router<S> {
+    pub fn group(&mut self, path: &str, router: Router<S>) -> Self {
         if !path.starts_with('/') {
             panic!("group routes must start with '/'");
         }
 
-        for (_, endpoint) in router.index_endpoint {
-            let path = format!("{}{}", path, endpoint.path);
-            for (method, hadnler) in endpoint.method_handler {
-                self.route((method, path.clone(), hadnler));
+        if !router.hosts.is_empty() {
+            panic!("group routes must not contain host routers, use nest instead");
+        }
+
+        for endpoint in router.index_endpoint.values() {
+            for route in endpoint.routes() {
+                let mut route = route.clone();
+                route.path = format!("{}{}", path, route.path);
+                route.handler = router.apply_middleware(route.handler);
+                let index = self.add_route(route);
+                self.disable_auto(index, &router, endpoint);
             }
         }
 
+        for (prefix, nested) in &router.nested {
+            let prefix = format!("{}{}", path, prefix);
+            self.push_nested(prefix, router.wrap_nested(nested));
+        }
+
+        for (name, route_path) in router.names.iter() {
+            self.names
+                .insert(name.to_string(), format!("{}{}", path, route_path));
+        }
+
         self.clone()
     }
 
-    pub fn route<T>(&mut self, route: T) -> Self
+    pub fn route<T, M>(&mut self, route: T) -> Self
     where
-        T: Into<Route>,
+        T: IntoRoute<S, M>,
     {
-        let Route {
-            method,
-            path,
-            handler,
-        } = route.into();
-
-        if let Ok(matched) = self.path_index.at(&path) {
-            if let Some(endpoint) = self.index_endpoint.get_mut(matched.value) {
-                endpoint.method_handler.insert(method, handler);
-            }
-        } else {
-            let index = self.index;
-            self.path_index
-                .insert(&path, index)
-                .expect("path_index insert failed");
-            let endpoint = Endpoint::new()
-                .path(&path)
-                .method_handler(&method, &handler);
-            self.index_endpoint.insert(index, endpoint);
-            self.index += 1;
+        for route in route.into_routes() {
+            self.add_route(route);
+        }
+
+        self.clone()
+    }
+
+    fn add_route(&mut self, mut route: Route) -> usize {
+        let (path, constraints) = match PathConstraints::parse(&route.path) {
+            Ok(parsed) => parsed,
+            Err(e) => panic!("invalid route path `{}`: {e}", route.path),
+        };
+        route.path = path;
+        route.constraints.extend(constraints);
+
+        if let Some(name) = &route.name {
+            self.names.insert(name.clone(), route.path.clone());
+        }
+
+        let path = &route.path;
+        let pattern = normalize_pattern(path);
+        let index = match self.pattern_index.get(&pattern) {
+            Some(index) => *index,
+            None => {
+                let index = self.index;
+                if let Err(e) = self.path_index.insert(path, index) {
+                    if !self.can_shadow(&route, &e) {
+                        panic!("invalid route path `{path}`: {e}");
+                    }
+                    self.shadowed = true;
+                }
+                self.pattern_index.insert(pattern, index);
+                self.index_endpoint
+                    .insert(index, Endpoint::new().path(path));
+                self.index += 1;
+                index
+            }
+        };
+
+        if let Some(endpoint) = self.index_endpoint.get_mut(&index) {
+            endpoint.method_route(route);
+        }
+
+        index
+    }
+
+    // A pattern matchit cannot hold next to another, such as `/files/:id<u32>` beside
+    // `/files/*rest`, is still allowed when a constraint decides between the two.
+    fn can_shadow(&self, route: &Route, error: &InsertError) -> bool {
+        let InsertError::Conflict { with } = error else {
+            return false;
         };
 
+        let constrained = |route: &Route| !route.constraints.is_empty();
+        constrained(route)
+            || self
+                .pattern_index
+                .get(&normalize_pattern(with))
+                .and_then(|index| self.index_endpoint.get(index))
+                .is_some_and(|endpoint| endpoint.routes().any(constrained))
+    }
+
+    fn disable_auto(&mut self, index: usize, router: &Router<S>, endpoint: &Endpoint) {
+        if let Some(merged) = self.index_endpoint.get_mut(&index) {
+            merged.disable_auto_head |= router.disable_auto_head || endpoint.disable_auto_head;
+            merged.disable_auto_options |=
+                router.disable_auto_options || endpoint.disable_auto_options;
+        }
+    }
+
+    pub fn merge(&mut self, router: Router<S>) -> Self {
+        for endpoint in router.index_endpoint.values() {
+            for route in endpoint.routes() {
+                let mut route = route.clone();
+                route.handler = router.apply_middleware(route.handler);
+                let index = self.add_route(route);
+                self.disable_auto(index, &router, endpoint);
+            }
+        }
+
+        for (prefix, nested) in &router.nested {
+            self.push_nested(prefix.clone(), router.wrap_nested(nested));
+        }
+
+        for (pattern, host) in &router.hosts {
+            self.host(pattern.as_str(), router.wrap_nested(host));
+        }
+
+        for (name, path) in router.names.iter() {
+            self.names.insert(name.to_string(), path.to_string());
+        }
+
+        if self.fallback.is_none() {
+            self.fallback = router
+                .fallback
+                .clone()
+                .map(|handler| router.apply_middleware(handler));
+        }
+
+        if self.method_not_allowed_fallback.is_none() {
+            self.method_not_allowed_fallback = router
+                .method_not_allowed_fallback
+                .clone()
+                .map(|handler| router.apply_middleware(handler));
+        }
+
         self.clone()
     }
 
-    pub fn merge(&mut self, router: Router) -> Self {
-        for (_, endpoint) in router.index_endpoint {
-            for (method, hadnler) in endpoint.method_handler {
-                self.route((method, endpoint.path.clone(), hadnler));
+    pub fn nest(&mut self, prefix: &str, router: Router<S>) -> Self {
+        if !prefix.starts_with('/') || prefix.ends_with('/') {
+            panic!("nest prefix must start with '/' and must not end with '/'");
+        }
+
+        if prefix.contains([':', '*']) {
+            panic!("nest prefix must not contain path params");
+        }
+
+        for (name, path) in router.names.iter() {
+            self.names.insert(name.to_string(), join_path(prefix, path));
+        }
+
+        self.push_nested(prefix.to_string(), router);
+        self.clone()
+    }
+
+    fn push_nested(&mut self, prefix: String, router: Router<S>) {
+        match self.nested.iter_mut().find(|(nested, _)| *nested == prefix) {
+            Some((_, nested)) => {
+                *nested = Router::default().merge(nested.clone()).merge(router);
             }
+            None => self.nested.push((prefix, router)),
         }
 
+        self.nested.sort_by_key(|(prefix, _)| Reverse(prefix.len()));
+    }
+
+    pub fn host(&mut self, pattern: &str, router: Router<S>) -> Self {
+        let pattern = match HostPattern::parse(pattern) {
+            Ok(pattern) => pattern,
+            Err(e) => panic!("invalid host pattern: {e}"),
+        };
+
+        for (name, path) in router.names.iter() {
+            self.names.insert(name.to_string(), path.to_string());
+        }
+
+        match self.hosts.iter_mut().find(|(host, _)| *host == pattern) {
+            Some((_, host)) => {
+                host.merge(router);
+            }
+            None => self.hosts.push((pattern, router)),
+        }
+
+        self.hosts.sort_by_key(|(pattern, _)| !pattern.is_exact());
         self.clone()
     }
 
-    pub fn get_handler(&self, method: &Method, path: &str) -> Option<(String, Handler)> {
-        if let Ok(matchit) = self.path_index.at(path) {
-            if let Some(endpoint) = self.index_endpoint.get(matchit.value) {
-                if let Some(handler) = endpoint.method_handler.get(method) {
-                    return Some((endpoint.path.clone(), handler.clone()));
-                }
+    pub fn fallback<Func, Param>(&mut self, func: Func) -> Self
+    where
+        Func: HandlerFuncParam<Param> + Sync + Clone + 'static,
+        Param: Send + Sync + 'static,
+    {
+        self.fallback = Some(FuncParamHandler::from(func).into());
+        self.clone()
+    }
+
+    pub fn method_not_allowed_fallback<Func, Param>(&mut self, func: Func) -> Self
+    where
+        Func: HandlerFuncParam<Param> + Sync + Clone + 'static,
+        Param: Send + Sync + 'static,
+    {
+        self.method_not_allowed_fallback = Some(FuncParamHandler::from(func).into());
+        self.clone()
+    }
+
+    pub fn auto_head(&mut self, enabled: bool) -> Self {
+        self.disable_auto_head = !enabled;
+        self.clone()
+    }
+
+    pub fn auto_options(&mut self, enabled: bool) -> Self {
+        self.disable_auto_options = !enabled;
+        self.clone()
+    }
+
+    pub fn routes(&self) -> RouteTable {
+        let mut routes = vec![];
+        self.collect_routes("", None, &mut routes);
+        routes.sort_by(|a, b| {
+            a.host.cmp(&b.host).then(a.path.cmp(&b.path)).then_with(|| {
+                let a = a.method.as_ref().map(Method::as_str);
+                let b = b.method.as_ref().map(Method::as_str);
+                a.cmp(&b)
+            })
+        });
+        RouteTable(routes)
+    }
+
+    fn collect_routes(&self, prefix: &str, host: Option<&str>, routes: &mut Vec<RouteInfo>) {
+        for endpoint in self.index_endpoint.values() {
+            for route in endpoint.routes() {
+                let middleware = route.handler.middleware.as_ref().map_or(0, VecDeque::len);
+                routes.push(RouteInfo {
+                    host: host.map(str::to_string),
+                    method: route.method.clone(),
+                    path: join_path(prefix, &route.path),
+                    name: route.name.clone(),
+                    constraints: route.constraints.clone(),
+                    middleware: middleware + self.middleware.len(),
+                    #[cfg(feature = "openapi")]
+                    operation: route.operation.clone(),
+                });
             }
         }
 
-        None
+        for (nest_prefix, router) in &self.nested {
+            let prefix = format!("{prefix}{nest_prefix}");
+            self.wrap_nested(router)
+                .collect_routes(&prefix, host, routes);
+        }
+
+        for (pattern, router) in &self.hosts {
+            self.wrap_nested(router)
+                .collect_routes(prefix, Some(pattern.as_str()), routes);
+        }
+    }
+
+    #[cfg(feature = "openapi")]
+    pub fn openapi(&self) -> OpenApi {
+        OpenApi::new(self.routes())
+    }
+
+    pub fn url_for<I, K, V>(&self, name: &str, params: I) -> Result<String, UrlForError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.names.url_for(name, params)
     }
 
     pub fn middleware<T>(&mut self, middleware: T) -> Self
     where
         T: Into<Box<dyn Middleware>>,
     {
-        let middleware = middleware.into();
-        for endpoint in self.index_endpoint.borrow_mut().values_mut() {
-            for handler in endpoint.method_handler.borrow_mut().values_mut() {
-                handler.middleware(middleware.clone());
-            }
+        self.middleware.push_back(middleware.into());
+        self.clone()
+    }
+
+    pub fn freeze(&self) -> FrozenRouter {
+        self.freeze_with(&AppState::default())
+    }
+
+    /// Freezes the router and resolves the state each route reads from `state`.
+    pub fn freeze_with(&self, state: &AppState) -> FrozenRouter {
+        self.freeze_at(None, state)
+    }
+
+    fn freeze_at(&self, nested_path: Option<Arc<str>>, state: &AppState) -> FrozenRouter {
+        let prefix = nested_path.as_deref().unwrap_or("");
+        let index_endpoint = self
+            .index_endpoint
+            .iter()
+            .map(|(index, endpoint)| (*index, endpoint.freeze(self, prefix, state)))
+            .collect();
+
+        let nested = self
+            .nested
+            .iter()
+            .map(|(nest_prefix, router)| {
+                let nested_path = format!("{prefix}{nest_prefix}");
+                let router = self.wrap_nested(router);
+                (
+                    nest_prefix.clone(),
+                    router.freeze_at(Some(nested_path.into()), state),
+                )
+            })
+            .collect();
+
+        let hosts = self
+            .hosts
+            .iter()
+            .map(|(pattern, router)| {
+                let router = self.wrap_nested(router);
+                (
+                    pattern.clone(),
+                    router.freeze_at(nested_path.clone(), state),
+                )
+            })
+            .collect();
+
+        FrozenRouter {
+            path_index: self.path_index.clone(),
+            fallthrough: self.fallthrough(),
+            shadowed: self.shadowed,
+            index_endpoint,
+            fallback: self
+                .fallback
+                .clone()
+                .map(|handler| self.apply_middleware(handler).into()),
+            method_not_allowed_fallback: self
+                .method_not_allowed_fallback
+                .clone()
+                .map(|handler| self.apply_middleware(handler).into()),
+            nested,
+            nested_path,
+            hosts,
+            names: Arc::new(self.names.clone()),
         }
+    }
 
-        self.clone()
+    fn fallthrough(&self) -> Vec<(matchit::Router<usize>, usize)> {
+        let constrained = self
+            .index_endpoint
+            .values()
+            .flat_map(Endpoint::routes)
+            .any(|route| !route.constraints.is_empty());
+        if !constrained {
+            return vec![];
+        }
+
+        // Same order matchit prefers: static segments, then params, then catch-alls.
+        let mut endpoints = self.index_endpoint.iter().collect::<Vec<_>>();
+        endpoints.sort_by_key(|(index, endpoint)| {
+            let segments = endpoint
+                .path
+                .split('/')
+                .map(|segment| match segment.find([':', '*']) {
+                    Some(start) => 1 + usize::from(&segment[start..=start] == "*"),
+                    None => 0,
+                })
+                .collect::<Vec<_>>();
+            (segments, **index)
+        });
+
+        endpoints
+            .into_iter()
+            .map(|(index, endpoint)| {
+                let mut patterns = matchit::Router::new();
+                if let Err(e) = patterns.insert(endpoint.path.clone(), *index) {
+                    panic!("invalid route path `{}`: {e}", endpoint.path);
+                }
+                (patterns, *index)
+            })
+            .collect()
+    }
+
+    fn wrap_nested(&self, router: &Router<S>) -> Router<S> {
+        let mut router = router.clone();
+        for middleware in &self.middleware {
+            router.middleware(middleware.clone());
+        }
+
+        router
+    }
+
+    fn apply_middleware(&self, mut handler: Handler) -> Handler {
+        for middleware in &self.middleware {
+            handler.middleware(middleware.clone());
+        }
+
+        handler
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, Request};
+
+    use crate::route::Route;
+
+    use super::{FrozenRouter, RouteError, Router};
+
+    fn route(method: Method, path: &str) -> Route {
+        Route::init(method, path, || async { "" })
+    }
+
+    #[test]
+    fn route_table_lists_prefixed_routes() {
+        let users = Router::new()
+            .route(route(Method::GET, "/users/:id").name("user"))
+            .route(route(Method::POST, "/users"));
+        let router = Router::new()
+            .route(route(Method::GET, "/"))
+            .group("/api", users)
+            .nest(
+                "/admin",
+                Router::new().route(route(Method::DELETE, "/cache")),
+            );
+
+        assert_eq!(
+            router.routes().to_string(),
+            "METHOD  PATH            NAME  MIDDLEWARE\n\
+             GET     /               -     0\n\
+             DELETE  /admin/cache    -     0\n\
+             POST    /api/users      -     0\n\
+             GET     /api/users/:id  user  0\n"
+        );
+    }
+
+    fn matched(
+        router: &FrozenRouter,
+        uri: &str,
+    ) -> Result<(String, Vec<(String, String)>), RouteError> {
+        let (parts, _) = Request::get(uri).body(()).unwrap().into_parts();
+        router
+            .get_handler(&parts)
+            .map(|matched| {
+                let params = matched
+                    .params
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                (matched.path.to_string(), params)
+            })
+            .map_err(|miss| miss.error)
+    }
+
+    #[test]
+    fn static_route_is_not_folded_into_constrained_route() {
+        let router = Router::new()
+            .route(route(Method::GET, "/users/:id<u32>"))
+            .route(route(Method::GET, "/users/new"))
+            .freeze();
+
+        assert_eq!(
+            matched(&router, "/users/7"),
+            Ok((
+                "/users/:id".to_string(),
+                vec![("id".to_string(), "7".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/users/new"),
+            Ok(("/users/new".to_string(), vec![]))
+        );
+        assert_eq!(matched(&router, "/users/abc"), Err(RouteError::NotFound));
+    }
+
+    #[test]
+    fn sibling_constrained_routes_keep_their_param_names() {
+        let router = Router::new()
+            .route(route(Method::GET, "/items/:id<u32>"))
+            .route(route(Method::GET, "/items/:slug<[a-z-]+>"));
+
+        assert_eq!(
+            router.routes().to_string(),
+            "METHOD  PATH                   NAME  MIDDLEWARE\n\
+             GET     /items/:id<u32>        -     0\n\
+             GET     /items/:slug<[a-z-]+>  -     0\n"
+        );
+
+        let router = router.freeze();
+        assert_eq!(
+            matched(&router, "/items/7"),
+            Ok((
+                "/items/:id".to_string(),
+                vec![("id".to_string(), "7".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/items/red-hat"),
+            Ok((
+                "/items/:slug".to_string(),
+                vec![("slug".to_string(), "red-hat".to_string())]
+            ))
+        );
+        assert_eq!(matched(&router, "/items/Red"), Err(RouteError::NotFound));
+    }
+
+    #[test]
+    fn constraint_mismatch_falls_through_to_other_routes() {
+        let router = Router::new()
+            .route(route(Method::GET, "/files/:id<u32>"))
+            .route(route(Method::GET, "/files/*rest"))
+            .route(route(Method::GET, "/docs/*rest"))
+            .route(route(Method::GET, "/docs/:page<u32>"))
+            .freeze();
+
+        assert_eq!(
+            matched(&router, "/files/7"),
+            Ok((
+                "/files/:id".to_string(),
+                vec![("id".to_string(), "7".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/files/abc"),
+            Ok((
+                "/files/*rest".to_string(),
+                vec![("rest".to_string(), "abc".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/files/7/readme.md"),
+            Ok((
+                "/files/*rest".to_string(),
+                vec![("rest".to_string(), "7/readme.md".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/docs/3"),
+            Ok((
+                "/docs/:page".to_string(),
+                vec![("page".to_string(), "3".to_string())]
+            ))
+        );
+        assert_eq!(
+            matched(&router, "/docs/intro"),
+            Ok((
+                "/docs/*rest".to_string(),
+                vec![("rest".to_string(), "intro".to_string())]
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid route path `/files/*rest`")]
+    fn unconstrained_conflicting_routes_panic() {
+        Router::new()
+            .route(route(Method::GET, "/files/:id"))
+            .route(route(Method::GET, "/files/*rest"));
+    }
+
+    #[test]
+    fn longer_nest_prefix_wins_after_merge_and_group() {
+        let api = Router::new().nest("/api", Router::new().route(route(Method::GET, "/users")));
+        let v2 = Router::new().nest("/api/v2", Router::new().route(route(Method::GET, "/users")));
+        let merged = Router::new().merge(api).merge(v2).freeze();
+        assert_eq!(
+            matched(&merged, "/api/v2/users"),
+            Ok(("/api/v2/users".to_string(), vec![]))
+        );
+
+        let grouped = Router::new()
+            .nest("/api", Router::new().route(route(Method::GET, "/users")))
+            .group(
+                "/api",
+                Router::new().nest("/v2", Router::new().route(route(Method::GET, "/users"))),
+            )
+            .freeze();
+        assert_eq!(
+            matched(&grouped, "/api/v2/users"),
+            Ok(("/api/v2/users".to_string(), vec![]))
+        );
+        assert_eq!(
+            matched(&grouped, "/api/users"),
+            Ok(("/api/users".to_string(), vec![]))
+        );
+    }
+
+    #[test]
+    fn nesting_twice_under_one_prefix_merges_the_routers() {
+        let router = Router::new()
+            .nest("/api", Router::new().route(route(Method::GET, "/users")))
+            .nest("/api", Router::new().route(route(Method::GET, "/posts")))
+            .freeze();
+
+        assert_eq!(
+            matched(&router, "/api/users"),
+            Ok(("/api/users".to_string(), vec![]))
+        );
+        assert_eq!(
+            matched(&router, "/api/posts"),
+            Ok(("/api/posts".to_string(), vec![]))
+        );
     }
 }
@@ -0,0 +1,79 @@
+use std::{fmt::Debug, sync::Arc};
+
+use http::Extensions;
+
+#[derive(Clone, Default)]
+pub struct AppState(Arc<Extensions>);
+
+impl AppState {
+    pub fn get<T>(&self) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.0.get::<T>()
+    }
+
+    pub fn insert<T>(&mut self, value: T)
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        Arc::make_mut(&mut self.0).insert(value);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn find<T>(extensions: &Extensions) -> Option<&T>
+    where
+        T: Send + Sync + 'static,
+    {
+        extensions
+            .get::<T>()
+            .or_else(|| extensions.get::<RouteState>()?.0.get::<T>())
+            .or_else(|| extensions.get::<AppState>()?.get::<T>())
+    }
+}
+
+/// The state values resolved for one route when its router was frozen.
+#[derive(Clone, Default)]
+pub struct RouteState(pub AppState);
+
+impl Debug for RouteState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteState").finish_non_exhaustive()
+    }
+}
+
+pub trait FromRef<S> {
+    fn from_ref(input: &S) -> Self;
+}
+
+impl<T> FromRef<T> for T
+where
+    T: Clone,
+{
+    fn from_ref(input: &T) -> Self {
+        input.clone()
+    }
+}
+
+pub struct NoState;
+
+pub trait ProvideState<T> {
+    fn provide(&self, route_state: &mut AppState);
+}
+
+impl<T> ProvideState<T> for NoState {
+    fn provide(&self, _: &mut AppState) {}
+}
+
+impl<S, T> ProvideState<T> for S
+where
+    S: Clone + Send + Sync + 'static,
+    T: FromRef<S> + Clone + Send + Sync + 'static,
+{
+    fn provide(&self, route_state: &mut AppState) {
+        route_state.insert(T::from_ref(self));
+    }
+}
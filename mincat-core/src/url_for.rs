@@ -0,0 +1,148 @@
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlForError {
+    UnknownRoute(String),
+    MissingParam(String),
+}
+
+impl Display for UrlForError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlForError::UnknownRoute(name) => write!(f, "unknown route name: {name}"),
+            UrlForError::MissingParam(param) => write!(f, "missing route param: {param}"),
+        }
+    }
+}
+
+impl Error for UrlForError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct RouteNames(HashMap<String, String>);
+
+impl RouteNames {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, path)| (name.as_str(), path.as_str()))
+    }
+
+    pub fn url_for<I, K, V>(&self, name: &str, params: I) -> Result<String, UrlForError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let path = self
+            .get(name)
+            .ok_or_else(|| UrlForError::UnknownRoute(name.to_string()))?;
+        let params = params
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_string(), value.as_ref().to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let mut url = String::with_capacity(path.len());
+        let mut rest = path;
+        while let Some(start) = rest.find([':', '*']) {
+            url.push_str(&rest[..start]);
+            let wildcard = rest[start..].starts_with('*');
+            let end = rest[start..]
+                .find('/')
+                .map_or(rest.len(), |end| start + end);
+            let key = &rest[start + 1..end];
+            let value = params
+                .get(key)
+                .ok_or_else(|| UrlForError::MissingParam(key.to_string()))?;
+
+            if wildcard {
+                let segments = value
+                    .trim_start_matches('/')
+                    .split('/')
+                    .map(|segment| utf8_percent_encode(segment, PATH_SEGMENT).to_string())
+                    .collect::<Vec<_>>();
+                url.push_str(&segments.join("/"));
+            } else {
+                url.extend(utf8_percent_encode(value, PATH_SEGMENT));
+            }
+
+            rest = &rest[end..];
+        }
+
+        url.push_str(rest);
+        Ok(url)
+    }
+
+    pub(crate) fn insert(&mut self, name: String, path: String) {
+        if let Some(exists) = self.0.get(&name) {
+            if *exists != path {
+                panic!("route name `{name}` is already used by `{exists}`");
+            }
+        }
+
+        self.0.insert(name, path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteNames, UrlForError};
+
+    fn names() -> RouteNames {
+        let mut names = RouteNames::default();
+        names.insert("user".to_string(), "/users/:id".to_string());
+        names.insert("file".to_string(), "/files/*path".to_string());
+        names
+    }
+
+    #[test]
+    fn percent_encodes_param_values() {
+        let url = names().url_for("user", [("id", "a b/c?d")]).unwrap();
+        assert_eq!(url, "/users/a%20b%2Fc%3Fd");
+    }
+
+    #[test]
+    fn keeps_slashes_in_wildcard_values() {
+        let url = names()
+            .url_for("file", [("path", "/docs/read me.md")])
+            .unwrap();
+        assert_eq!(url, "/files/docs/read%20me.md");
+    }
+
+    #[test]
+    fn reports_unknown_names_and_missing_params() {
+        let names = names();
+        assert_eq!(
+            names.url_for("post", [("id", "1")]),
+            Err(UrlForError::UnknownRoute("post".to_string()))
+        );
+        assert_eq!(
+            names.url_for("user", [("name", "1")]),
+            Err(UrlForError::MissingParam("id".to_string()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "route name `user` is already used by `/users/:id`")]
+    fn rejects_a_name_used_for_another_path() {
+        names().insert("user".to_string(), "/members/:id".to_string());
+    }
+}
@@ -4,12 +4,25 @@ mod extract_form;
 mod generics_param;
 mod method_handler;
 mod middleware;
+mod path_fields;
+mod route_path;
+mod schema;
 
 #[proc_macro_derive(Form, attributes(multer))]
 pub fn derive_form(input: TokenStream) -> TokenStream {
     extract_form::generate(input)
 }
 
+#[proc_macro_derive(ToSchema, attributes(serde))]
+pub fn derive_to_schema(input: TokenStream) -> TokenStream {
+    schema::generate(input)
+}
+
+#[proc_macro_derive(PathFields, attributes(serde))]
+pub fn derive_path_fields(input: TokenStream) -> TokenStream {
+    path_fields::generate(input)
+}
+
 #[proc_macro_attribute]
 pub fn middleware(_: TokenStream, input: TokenStream) -> TokenStream {
     middleware::generate(input)
@@ -24,7 +37,7 @@ macro_rules! generate_route_attribute {
     ($name: ident, $method: expr) => {
         #[proc_macro_attribute]
         pub fn $name(args: TokenStream, input: TokenStream) -> TokenStream {
-            method_handler::generate($method, args, input)
+            method_handler::generate(method_handler::Methods::Fixed($method), args, input)
         }
     };
 }
@@ -36,3 +49,15 @@ generate_route_attribute!(delete, "DELETE");
 generate_route_attribute!(head, "HEAD");
 generate_route_attribute!(patch, "PATCH");
 generate_route_attribute!(options, "OPTIONS");
+generate_route_attribute!(trace, "TRACE");
+generate_route_attribute!(connect, "CONNECT");
+
+#[proc_macro_attribute]
+pub fn any(args: TokenStream, input: TokenStream) -> TokenStream {
+    method_handler::generate(method_handler::Methods::Any, args, input)
+}
+
+#[proc_macro_attribute]
+pub fn route(args: TokenStream, input: TokenStream) -> TokenStream {
+    method_handler::generate(method_handler::Methods::Listed, args, input)
+}
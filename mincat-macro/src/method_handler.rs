@@ -1,45 +1,433 @@
 use quote::{format_ident, quote};
-use syn::{parse_macro_input, ItemFn, LitStr};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Expr, FnArg, Ident, ItemFn, LitStr, Token, Type,
+};
+#[cfg(feature = "openapi")]
+use syn::{GenericArgument, PathArguments};
+
+use crate::route_path;
+
+const STANDARD_METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "DELETE", "HEAD", "OPTIONS", "CONNECT", "PATCH", "TRACE",
+];
+
+pub enum Methods {
+    Fixed(&'static str),
+    Any,
+    Listed,
+}
+
+struct RouteArgs {
+    methods: Vec<Ident>,
+    path: Option<LitStr>,
+    prefix: Option<LitStr>,
+    name: Option<LitStr>,
+    middleware: Vec<Expr>,
+    guards: Vec<Expr>,
+}
+
+impl Parse for RouteArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut methods = vec![];
+        let mut path = None;
+        let mut prefix = None;
+        let mut name = None;
+        let mut middleware = vec![];
+        let mut guards = vec![];
+
+        while !input.is_empty() {
+            if input.peek(LitStr) {
+                if path.is_some() {
+                    return Err(input.error("route path is already set"));
+                }
+                path = Some(input.parse::<LitStr>()?);
+            } else {
+                let key = input.parse::<Ident>()?;
+                if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    match key.to_string().as_str() {
+                        "path" if path.is_none() => path = Some(input.parse::<LitStr>()?),
+                        "path" => {
+                            return Err(syn::Error::new(key.span(), "route path is already set"))
+                        }
+                        "prefix" => prefix = Some(input.parse::<LitStr>()?),
+                        "name" => name = Some(input.parse::<LitStr>()?),
+                        "middleware" => {
+                            let content;
+                            bracketed!(content in input);
+                            middleware
+                                .extend(Punctuated::<Expr, Token![,]>::parse_terminated(&content)?);
+                        }
+                        "guard" => guards.push(input.parse::<Expr>()?),
+                        _ => return Err(syn::Error::new(key.span(), "unknown route argument")),
+                    }
+                } else {
+                    methods.push(key);
+                }
+            }
+
+            if input.is_empty() {
+                break;
+            }
+
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(Self {
+            methods,
+            path,
+            prefix,
+            name,
+            middleware,
+            guards,
+        })
+    }
+}
+
+fn method_tokens(method: &Ident) -> syn::Result<proc_macro2::TokenStream> {
+    let value = method.to_string();
+    if STANDARD_METHODS.contains(&value.as_str()) {
+        return Ok(quote!(mincat::http::Method::#method));
+    }
+
+    if !value.bytes().all(|byte| byte.is_ascii_uppercase()) {
+        return Err(syn::Error::new(
+            method.span(),
+            "custom methods must be uppercase ASCII letters",
+        ));
+    }
+
+    let bytes = syn::LitByteStr::new(value.as_bytes(), method.span());
+    Ok(quote!(mincat::http::Method::from_bytes(#bytes).expect("invalid method")))
+}
+
+fn route_constructors(
+    methods: Methods,
+    args: &RouteArgs,
+) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let path = args
+        .path
+        .as_ref()
+        .ok_or_else(|| syn::Error::new(proc_macro2::Span::call_site(), "missing route path"))?;
+
+    let methods = match methods {
+        Methods::Fixed(method) => {
+            if let Some(method) = args.methods.first() {
+                return Err(syn::Error::new(method.span(), "unknown route argument"));
+            }
+
+            vec![Some(format_ident!("{}", method))]
+        }
+        Methods::Any => {
+            if let Some(method) = args.methods.first() {
+                return Err(syn::Error::new(method.span(), "unknown route argument"));
+            }
+
+            vec![None]
+        }
+        Methods::Listed => {
+            if args.methods.is_empty() {
+                return Err(syn::Error::new(
+                    path.span(),
+                    "route requires at least one method",
+                ));
+            }
+
+            let mut methods = vec![];
+            for method in &args.methods {
+                if methods.contains(&Some(method.clone())) {
+                    return Err(syn::Error::new(method.span(), "duplicate route method"));
+                }
+
+                methods.push(Some(method.clone()));
+            }
+
+            methods
+        }
+    };
+
+    methods
+        .iter()
+        .map(|method| match method {
+            Some(method) => {
+                let method = method_tokens(method)?;
+                Ok(quote!(mincat::http::Route::init(#method, #path, __handler)))
+            }
+            None => Ok(quote!(mincat::http::Route::any(#path, __handler))),
+        })
+        .collect()
+}
+
+fn arg_types(func: &ItemFn) -> Vec<&Type> {
+    func.sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(input) => Some(input.ty.as_ref()),
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn path_checks(func: &ItemFn) -> proc_macro2::TokenStream {
+    let checks = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(input) if route_path::path_type(&input.ty).is_some() => {
+                Some(input.ty.as_ref())
+            }
+            _ => None,
+        })
+        .map(|ty| {
+            quote!(
+                if let Some(check) = (&&mincat::http::__private::CheckPath::<#ty>::new()).path_check() {
+                    route.path_check(check);
+                }
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if checks.is_empty() {
+        return quote!();
+    }
+
+    quote!({
+        #[allow(unused_imports)]
+        use mincat::http::__private::{CheckPathParams as _, CheckPathParamsFallback as _};
+        #(#checks)*
+    })
+}
+
+#[cfg(feature = "openapi")]
+fn last_segment_ident(ty: &Type) -> Option<(String, &PathArguments)> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+
+    ty.path
+        .segments
+        .last()
+        .map(|segment| (segment.ident.to_string(), &segment.arguments))
+}
+
+#[cfg(feature = "openapi")]
+fn operation(func: &ItemFn) -> proc_macro2::TokenStream {
+    let docs = func
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) => match &meta.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(doc),
+                    ..
+                }) => Some(doc.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let summary = docs
+        .first()
+        .filter(|summary| !summary.is_empty())
+        .map(|summary| quote!(operation.summary(#summary);));
+    let description = docs
+        .iter()
+        .skip(1)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    let description =
+        (!description.is_empty()).then(|| quote!(operation.description(#description);));
+
+    let inputs = func
+        .sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(input) => Some(input.ty.as_ref()),
+            FnArg::Receiver(_) => None,
+        })
+        .filter(|ty| {
+            last_segment_ident(ty).is_some_and(|(ident, _)| {
+                matches!(
+                    ident.as_str(),
+                    "Path" | "Query" | "Json" | "FormUrlencoded" | "FormData"
+                )
+            })
+        })
+        .map(|ty| {
+            quote!((&&mincat::openapi::__private::Describe::<#ty>::new()).describe_input(operation);)
+        });
+
+    let output = match &func.sig.output {
+        syn::ReturnType::Type(_, ty) => match last_segment_ident(ty) {
+            Some((ident, _)) if ident == "Json" => Some(ty),
+            Some((ident, PathArguments::AngleBracketed(args))) if ident == "Result" => {
+                match args.args.first() {
+                    Some(GenericArgument::Type(inner))
+                        if last_segment_ident(inner).is_some_and(|(ident, _)| ident == "Json") =>
+                    {
+                        Some(ty)
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    }
+    .map(|ty| {
+        quote!((&&mincat::openapi::__private::Describe::<#ty>::new()).describe_output(operation);)
+    });
+
+    quote!(
+        route.operation(|operation| {
+            #[allow(unused_imports)]
+            use mincat::openapi::__private::{
+                DescribeInput as _, DescribeInputFallback as _, DescribeOutput as _,
+                DescribeOutputFallback as _,
+            };
+            #summary
+            #description
+            #(#inputs)*
+            #output
+        });
+    )
+}
+
+#[cfg(not(feature = "openapi"))]
+fn operation(_: &ItemFn) -> proc_macro2::TokenStream {
+    quote!()
+}
 
 pub fn generate(
-    method: &str,
-    path: proc_macro::TokenStream,
+    methods: Methods,
+    args: proc_macro::TokenStream,
     func: proc_macro::TokenStream,
 ) -> proc_macro::TokenStream {
     let mut func = parse_macro_input!(func as ItemFn);
     let func_vis = func.vis.clone();
     let func_name = func.sig.ident.clone();
     let new_func_name = format_ident!("__origin__{}__", func.sig.ident);
-    let method = format_ident!("{}", method);
-    let method = quote!(mincat::http::Method::#method);
-    let path = parse_macro_input!(path as LitStr);
-    if !path.value().starts_with('/') {
-        panic!("route path must start with '/'");
-    }
+    let args = parse_macro_input!(args as RouteArgs);
+    let checked = route_constructors(methods, &args).and_then(|constructors| {
+        let path = args.path.as_ref().expect("route path is checked");
+        let params = route_path::route_params(args.prefix.as_ref(), path)?;
+        let field_checks = route_path::check_extractor(&func, &params)?;
+        Ok((constructors, field_checks))
+    });
+    let (constructors, field_checks) = match checked {
+        Ok(checked) => checked,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let name = args.name;
+    let middleware = args.middleware;
+    let guards = args.guards;
 
     func.sig.ident = new_func_name.clone();
+    let operation = operation(&func);
+    let path_checks = path_checks(&func);
+    let arg_types = arg_types(&func);
+    let route_name = match name {
+        Some(name) => quote!(route.name(#name);),
+        None => quote!(),
+    };
+    let route_middleware = quote!(#(route.middleware(#middleware);)*);
+    let route_guards = quote!(#(route.guard(#guards);)*);
+    let (into_route, provide_state) = match arg_types.split_last() {
+        Some((last, parts)) => (
+            quote!(
+                impl<__S, __M> mincat::http::IntoRoute<__S, __M> for #func_name
+                where
+                    __S: Send + Sync + 'static,
+                    #(#parts: mincat::http::FromRequestParts<__S>,)*
+                    #last: mincat::http::FromRequest<__S, __M>,
+            ),
+            quote!(
+                route.provide_state(|state, route_state| {
+                    if let Some(state) = state.get::<__S>() {
+                        #(<#parts as mincat::http::FromRequestParts<__S>>::provide_state(state, route_state);)*
+                        <#last as mincat::http::FromRequest<__S, __M>>::provide_state(state, route_state);
+                    }
+                });
+            ),
+        ),
+        None => (
+            quote!(
+                impl<__S> mincat::http::IntoRoute<__S> for #func_name
+                where
+                    __S: 'static,
+            ),
+            quote!(),
+        ),
+    };
+
+    let from_route = match constructors.as_slice() {
+        [constructor] => quote!(
+            impl From<#func_name> for mincat::http::Route {
+                #[allow(unused_mut)]
+                fn from(_:#func_name) -> mincat::http::Route {
+                    let __handler = #new_func_name;
+                    let mut route = #constructor;
+                    #route_name
+                    #route_guards
+                    #path_checks
+                    #operation
+                    #route_middleware
+                    route
+                }
+            }
+        ),
+        _ => quote!(),
+    };
 
     quote!(
         #[allow(non_snake_case)]
         #func
 
+        #field_checks
+
         #[allow(non_camel_case_types)]
         #[derive(Clone, Copy)]
         #func_vis struct #func_name;
 
         impl #func_name {
-            fn middleware<T>(&mut self, middleware: T) -> mincat::http::Route
+            fn middleware<T>(&mut self, middleware: T) -> mincat::http::WithMiddleware<#func_name>
             where
                 T: Into<Box<dyn mincat::middleware::Middleware>>,
             {
-                let mut route =  mincat::http::Route::init(#method, #path, #new_func_name);
-                route.middleware(middleware)
+                mincat::http::WithMiddleware::new(*self).middleware(middleware)
             }
         }
 
-        impl From<#func_name> for mincat::http::Route {
-            fn from(_:#func_name) -> mincat::http::Route {
-                mincat::http::Route::init(#method, #path, #new_func_name)
+        #from_route
+
+        #into_route
+        {
+            fn into_routes(self) -> Vec<mincat::http::Route> {
+                let __handler = #new_func_name;
+                vec![#(#constructors),*]
+                    .into_iter()
+                    .map(|mut route: mincat::http::Route| {
+                        #route_name
+                        #route_guards
+                        #path_checks
+                        #operation
+                        #provide_state
+                        #route_middleware
+                        route
+                    })
+                    .collect()
             }
         }
     )
@@ -0,0 +1,77 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Type};
+
+use crate::schema::serde_attribute;
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(ty) = ty else {
+        return false;
+    };
+
+    ty.path
+        .segments
+        .last()
+        .is_some_and(|segment| segment.ident == "Option")
+}
+
+fn derive_path_fields(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "PathFields can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            name,
+            "PathFields requires a struct with named fields",
+        ));
+    };
+
+    let container = serde_attribute(&input.attrs, "PathFields")?;
+    let mut fields = vec![];
+    for field in &named.named {
+        let attr = serde_attribute(&field.attrs, "PathFields")?;
+        if attr.skip || attr.skip_deserializing {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().unwrap().to_string();
+        let field_name = match (attr.rename, container.rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rule)) => rule.field(&ident),
+            (None, None) => ident,
+        };
+        let required = !(attr.default || container.default || is_option(&field.ty));
+        let unmatched =
+            format!("field `{field_name}` of {name} is not a parameter of the route pattern");
+        fields.push(quote! {
+            ::mincat::extract::PathField {
+                name: #field_name,
+                required: #required,
+                unmatched: #unmatched,
+            }
+        });
+    }
+
+    let deny_unknown = container.deny_unknown_fields;
+    Ok(quote! {
+        impl #impl_generics ::mincat::extract::PathFields for #name #ty_generics #where_clause {
+            const FIELDS: Option<&'static [::mincat::extract::PathField]> = Some(&[#(#fields),*]);
+            const DENY_UNKNOWN: bool = #deny_unknown;
+        }
+    })
+}
+
+pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
+    match derive_path_fields(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashSet;
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, FnArg, GenericArgument, ItemFn, LitStr, PathArguments, Type};
+
+use mincat_pattern::{check_constraint_type, param_names, split_constraints, CONSTRAINT_TYPES};
+
+const SCALAR_TYPES: &[&str] = &[
+    "bool", "char", "str", "String", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16",
+    "u32", "u64", "u128", "usize", "f32", "f64",
+];
+
+pub struct RouteParam {
+    pub name: String,
+    pub constraint: Option<String>,
+}
+
+fn strip_constraints(path: &LitStr) -> syn::Result<(String, Vec<(String, String)>)> {
+    let (plain, constraints) = split_constraints(&path.value())
+        .map_err(|e| syn::Error::new(path.span(), format!("invalid route path: {e}")))?;
+
+    for (name, source) in &constraints {
+        check_constraint_type(name, source)
+            .map_err(|e| syn::Error::new(path.span(), format!("invalid route path: {e}")))?;
+        if !CONSTRAINT_TYPES.contains(&source.as_str()) {
+            if let Err(e) = regex::Regex::new(&format!("^(?:{source})$")) {
+                return Err(syn::Error::new(
+                    path.span(),
+                    format!("invalid route path: invalid constraint for `{name}`: {e}"),
+                ));
+            }
+        }
+    }
+
+    Ok((plain, constraints))
+}
+
+pub fn params(path: &LitStr) -> syn::Result<Vec<RouteParam>> {
+    let (value, constraints) = strip_constraints(path)?;
+    if !value.starts_with('/') {
+        return Err(syn::Error::new(
+            path.span(),
+            "route path must start with '/'",
+        ));
+    }
+
+    if let Err(e) = matchit::Router::new().insert(value.as_str(), ()) {
+        return Err(syn::Error::new(
+            path.span(),
+            format!("invalid route path: {e}"),
+        ));
+    }
+
+    let mut params = vec![];
+    let mut names = HashSet::new();
+    for name in param_names(&value) {
+        if !names.insert(name) {
+            return Err(syn::Error::new(
+                path.span(),
+                format!("invalid route path: duplicate parameter `{name}`"),
+            ));
+        }
+
+        let constraint = constraints
+            .iter()
+            .find(|(constraint, _)| constraint == name)
+            .map(|(_, source)| source.clone());
+        params.push(RouteParam {
+            name: name.to_string(),
+            constraint,
+        });
+    }
+
+    Ok(params)
+}
+
+/// The parameters of `prefix` followed by those of `path`.
+pub fn route_params(prefix: Option<&LitStr>, path: &LitStr) -> syn::Result<Vec<RouteParam>> {
+    let mut res = match prefix {
+        Some(prefix) => params(prefix)?,
+        None => vec![],
+    };
+
+    for param in params(path)? {
+        if res.iter().any(|prefixed| prefixed.name == param.name) {
+            return Err(syn::Error::new(
+                path.span(),
+                format!(
+                    "invalid route path: parameter `{}` is already in the prefix",
+                    param.name
+                ),
+            ));
+        }
+        res.push(param);
+    }
+
+    Ok(res)
+}
+
+pub fn path_type(ty: &Type) -> Option<&Type> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+
+    let segment = ty.path.segments.last()?;
+    if segment.ident != "Path" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) => Some(ty),
+        _ => None,
+    }
+}
+
+fn check_constraint(ty: &Type, param: &RouteParam) -> syn::Result<()> {
+    let (Type::Path(type_path), Some(constraint)) = (ty, &param.constraint) else {
+        return Ok(());
+    };
+
+    let Some(segment) = type_path.path.segments.last() else {
+        return Ok(());
+    };
+
+    let ident = segment.ident.to_string();
+    if CONSTRAINT_TYPES.contains(&ident.as_str())
+        && CONSTRAINT_TYPES.contains(&constraint.as_str())
+        && ident != *constraint
+    {
+        return Err(syn::Error::new(
+            ty.span(),
+            format!(
+                "Path extracts `{}` as {ident} but the route pattern constrains it to {constraint}",
+                param.name
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks `Path` extractors against the parameters of the route attribute,
+/// which include those of its `prefix` argument.
+///
+/// Tuples must hold one value per parameter. The fields of other types are
+/// only known to their `PathFields` impl, so this returns `const` items that
+/// compare them with the parameters at compile time.
+pub fn check_extractor(func: &ItemFn, params: &[RouteParam]) -> syn::Result<TokenStream> {
+    let names = params
+        .iter()
+        .map(|param| param.name.as_str())
+        .collect::<Vec<_>>();
+    let mut checks = vec![];
+    for input in &func.sig.inputs {
+        let FnArg::Typed(input) = input else {
+            continue;
+        };
+
+        let Some(mut ty) = path_type(&input.ty) else {
+            continue;
+        };
+
+        while let Type::Paren(inner) = ty {
+            ty = &inner.elem;
+        }
+
+        match ty {
+            Type::Tuple(tuple) if tuple.elems.len() != params.len() => {
+                return Err(syn::Error::new(
+                    ty.span(),
+                    format!(
+                        "Path extracts {} value(s) but the route pattern has {} parameter(s): [{}]",
+                        tuple.elems.len(),
+                        params.len(),
+                        names.join(", ")
+                    ),
+                ));
+            }
+            Type::Tuple(tuple) => {
+                for (ty, param) in tuple.elems.iter().zip(params) {
+                    check_constraint(ty, param)?;
+                }
+            }
+            Type::Path(type_path) => {
+                let Some(segment) = type_path.path.segments.last() else {
+                    continue;
+                };
+
+                if SCALAR_TYPES.contains(&segment.ident.to_string().as_str()) {
+                    return Err(syn::Error::new(
+                        ty.span(),
+                        format!(
+                            "Path<{}> is not supported, use a tuple such as Path<({},)>",
+                            segment.ident, segment.ident
+                        ),
+                    ));
+                }
+
+                let unknown = names.iter().map(|name| {
+                    format!(
+                        "route parameter `{name}` is not a field of {}",
+                        segment.ident
+                    )
+                });
+                checks.push(quote_spanned! {ty.span()=>
+                    const _: () = mincat::http::__private::check_path_fields::<#ty>(
+                        &[#(#names),*],
+                        &[#(#unknown),*],
+                    );
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(quote!(#(#checks)*))
+}
+
+#[cfg(test)]
+mod tests {
+    use mincat_core::constraint::PathConstraints;
+    use proc_macro2::Span;
+    use syn::LitStr;
+
+    use mincat_pattern::param_names;
+
+    use super::params;
+
+    #[test]
+    fn parses_patterns_like_the_router() {
+        let patterns = [
+            "/plain",
+            "/users/:id<u32>",
+            "/files/*path<.+>",
+            "/posts/:year<\\d{4}>/:slug",
+            "/tags/:tag<a<b>c>",
+            "/posts/:state<(draft)>",
+        ];
+
+        for pattern in patterns {
+            let lit = LitStr::new(pattern, Span::call_site());
+            let params = params(&lit)
+                .unwrap()
+                .into_iter()
+                .map(|param| (param.name, param.constraint))
+                .collect::<Vec<_>>();
+
+            let (path, constraints) = PathConstraints::parse(pattern).unwrap();
+            let router = param_names(&path)
+                .into_iter()
+                .map(|name| {
+                    let source = constraints
+                        .iter()
+                        .find(|constraint| constraint.name() == name)
+                        .map(|constraint| constraint.source().to_string());
+                    (name.to_string(), source)
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(params, router, "{pattern}");
+        }
+
+        for pattern in [
+            "/users/:id<u32",
+            "/users/:id<>",
+            "/users/:id<(>",
+            "/users/:id<u46>",
+            "/users/:id<String>",
+        ] {
+            let lit = LitStr::new(pattern, Span::call_site());
+            assert!(params(&lit).is_err(), "{pattern}");
+            assert!(PathConstraints::parse(pattern).is_err(), "{pattern}");
+        }
+    }
+}
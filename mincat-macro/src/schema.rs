@@ -0,0 +1,291 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Fields, GenericArgument, GenericParam, LitStr, PathArguments,
+    Type,
+};
+
+const UNSUPPORTED_SERDE_ATTRIBUTES: &[&str] = &[
+    "flatten",
+    "tag",
+    "content",
+    "untagged",
+    "transparent",
+    "rename_all_fields",
+    "from",
+    "try_from",
+    "into",
+];
+
+#[derive(Default)]
+pub(crate) struct SerdeAttribute {
+    pub rename: Option<String>,
+    pub rename_all: Option<RenameAll>,
+    pub skip: bool,
+    pub skip_deserializing: bool,
+    pub default: bool,
+    pub deny_unknown_fields: bool,
+}
+
+pub(crate) fn serde_attribute(attrs: &[Attribute], derive: &str) -> syn::Result<SerdeAttribute> {
+    let mut res = SerdeAttribute::default();
+    for attr in attrs {
+        if !attr.path().is_ident("serde") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                res.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("rename_all") {
+                let rule = meta.value()?.parse::<LitStr>()?;
+                res.rename_all = Some(RenameAll::parse(&rule)?);
+            } else if meta.path.is_ident("skip") {
+                res.skip = true;
+            } else if meta.path.is_ident("skip_deserializing") {
+                res.skip_deserializing = true;
+            } else if meta.path.is_ident("deny_unknown_fields") {
+                res.deny_unknown_fields = true;
+            } else if meta.path.is_ident("default") {
+                res.default = true;
+                if meta.input.peek(syn::Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                }
+            } else if let Some(name) = UNSUPPORTED_SERDE_ATTRIBUTES
+                .iter()
+                .find(|name| meta.path.is_ident(name))
+            {
+                return Err(meta.error(format!("{derive} does not support #[serde({name})]")));
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            }
+
+            Ok(())
+        })?;
+    }
+
+    Ok(res)
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum RenameAll {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameAll {
+    fn parse(rule: &LitStr) -> syn::Result<Self> {
+        let rule = match rule.value().as_str() {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return Err(syn::Error::new(rule.span(), "unknown rename_all rule")),
+        };
+
+        Ok(rule)
+    }
+
+    pub fn field(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal | Self::Camel => {
+                let mut res = String::with_capacity(field.len());
+                let mut capitalize = matches!(self, Self::Pascal);
+                for c in field.chars() {
+                    if c == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        res.push(c.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        res.push(c);
+                    }
+                }
+                res
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.replace('_', "-").to_ascii_uppercase(),
+        }
+    }
+
+    fn variant(self, variant: &str) -> String {
+        match self {
+            Self::Lower => variant.to_ascii_lowercase(),
+            Self::Upper => variant.to_ascii_uppercase(),
+            Self::Pascal => variant.to_string(),
+            Self::Camel => variant[..1].to_ascii_lowercase() + &variant[1..],
+            Self::Snake | Self::ScreamingSnake | Self::Kebab | Self::ScreamingKebab => {
+                let mut snake = String::with_capacity(variant.len() + 4);
+                for (i, c) in variant.char_indices() {
+                    if i > 0 && c.is_uppercase() {
+                        snake.push('_');
+                    }
+                    snake.push(c.to_ascii_lowercase());
+                }
+                self.field(&snake)
+            }
+        }
+    }
+}
+
+fn form_file_schema(ty: &Type) -> Option<(TokenStream, bool)> {
+    let Type::Path(ty) = ty else {
+        return None;
+    };
+
+    let segment = ty.path.segments.last()?;
+    if segment.ident == "FormFile" {
+        let schema = quote!(::mincat::openapi::json!({ "type": "string", "format": "binary" }));
+        return Some((schema, true));
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+
+    let Some(GenericArgument::Type(inner)) = args.args.first() else {
+        return None;
+    };
+
+    let (schema, _) = form_file_schema(inner)?;
+    match segment.ident.to_string().as_str() {
+        "Option" => Some((schema, false)),
+        "Vec" => Some((
+            quote!(::mincat::openapi::json!({ "type": "array", "items": #schema })),
+            true,
+        )),
+        _ => None,
+    }
+}
+
+fn derive_to_schema(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    for generic in &mut input.generics.params {
+        if let GenericParam::Type(generic_ty) = generic {
+            generic_ty
+                .bounds
+                .push(syn::parse_str("::mincat::openapi::ToSchema")?);
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let container = serde_attribute(&input.attrs, "ToSchema")?;
+    let schema = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut properties = vec![];
+                for field in &fields.named {
+                    let attr = serde_attribute(&field.attrs, "ToSchema")?;
+                    if attr.skip {
+                        continue;
+                    }
+
+                    let ident = field.ident.as_ref().unwrap().to_string();
+                    let field_name = match (attr.rename, container.rename_all) {
+                        (Some(rename), _) => rename,
+                        (None, Some(rule)) => rule.field(&ident),
+                        (None, None) => ident,
+                    };
+                    let field_ty = &field.ty;
+                    let (schema, is_required) = match form_file_schema(field_ty) {
+                        Some((schema, is_required)) => (schema, quote!(#is_required)),
+                        None => (
+                            quote!(<#field_ty as ::mincat::openapi::ToSchema>::schema()),
+                            quote!(<#field_ty as ::mincat::openapi::ToSchema>::required()),
+                        ),
+                    };
+                    let is_required = match attr.default || container.default {
+                        true => quote!(false),
+                        false => is_required,
+                    };
+                    properties.push(quote! {
+                        properties.insert(#field_name.to_string(), #schema);
+                        if #is_required {
+                            required.push(::mincat::openapi::json!(#field_name));
+                        }
+                    });
+                }
+
+                quote! {
+                    let mut properties = ::mincat::openapi::Map::new();
+                    let mut required = Vec::<::mincat::openapi::Value>::new();
+                    #(#properties)*
+                    ::mincat::openapi::json!({
+                        "type": "object",
+                        "properties": properties,
+                        "required": required,
+                    })
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let field_ty = &fields.unnamed[0].ty;
+                quote!(<#field_ty as ::mincat::openapi::ToSchema>::schema())
+            }
+            Fields::Unnamed(fields) => {
+                let field_tys = fields.unnamed.iter().map(|field| &field.ty);
+                quote!(<(#(#field_tys,)*) as ::mincat::openapi::ToSchema>::schema())
+            }
+            Fields::Unit => quote!(::mincat::openapi::json!({ "type": "null" })),
+        },
+        Data::Enum(data) => {
+            let mut variants = vec![];
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "only unit enum variants are supported",
+                    ));
+                }
+
+                let attr = serde_attribute(&variant.attrs, "ToSchema")?;
+                if attr.skip {
+                    continue;
+                }
+
+                let ident = variant.ident.to_string();
+                variants.push(match (attr.rename, container.rename_all) {
+                    (Some(rename), _) => rename,
+                    (None, Some(rule)) => rule.variant(&ident),
+                    (None, None) => ident,
+                });
+            }
+
+            quote!(::mincat::openapi::json!({ "type": "string", "enum": [#(#variants),*] }))
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "unions are not supported",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::mincat::openapi::ToSchema for #name #ty_generics #where_clause {
+            fn schema() -> ::mincat::openapi::Value {
+                #schema
+            }
+        }
+    })
+}
+
+pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input as DeriveInput);
+    match derive_to_schema(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.into_compile_error().into(),
+    }
+}
@@ -0,0 +1,77 @@
+/// Types a constraint can name instead of a regex, as in `:id<u32>`.
+pub const CONSTRAINT_TYPES: &[&str] = &[
+    "bool", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64",
+];
+
+/// Splits `pattern` into the plain matchit path and the `(name, source)`
+/// pairs of the `<...>` constraints written after its parameters.
+pub fn split_constraints(pattern: &str) -> Result<(String, Vec<(String, String)>), String> {
+    let mut path = String::with_capacity(pattern.len());
+    let mut constraints = vec![];
+    let mut chars = pattern.char_indices().peekable();
+
+    while let Some((_, c)) = chars.next() {
+        path.push(c);
+        if c != ':' && c != '*' {
+            continue;
+        }
+
+        let mut name = String::new();
+        while let Some((_, c)) = chars.next_if(|(_, c)| *c != '/' && *c != '<') {
+            name.push(c);
+        }
+        path.push_str(&name);
+
+        let Some((start, _)) = chars.next_if(|(_, c)| *c == '<') else {
+            continue;
+        };
+
+        let mut depth = 1;
+        let end = loop {
+            match chars.next() {
+                Some((_, '<')) => depth += 1,
+                Some((end, '>')) if depth == 1 => break end,
+                Some((_, '>')) => depth -= 1,
+                Some(_) => {}
+                None => return Err(format!("unclosed constraint for `{name}`")),
+            }
+        };
+
+        let source = &pattern[start + 1..end];
+        if source.is_empty() {
+            return Err(format!("empty constraint for `{name}`"));
+        }
+
+        constraints.push((name, source.to_string()));
+    }
+
+    Ok((path, constraints))
+}
+
+/// Returns the names of the `:name` and `*name` parameters of a plain path.
+pub fn param_names(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| segment.find([':', '*']).map(|start| &segment[start + 1..]))
+        .collect()
+}
+
+/// Rejects a constraint that is written like a type name but is not one of
+/// [`CONSTRAINT_TYPES`], such as `u46` or `String`. Every other constraint is
+/// a regex; a literal word is matched with a group such as `<(draft)>`.
+pub fn check_constraint_type(name: &str, source: &str) -> Result<(), String> {
+    let mut chars = source.chars();
+    let is_ident = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if is_ident && !CONSTRAINT_TYPES.contains(&source) {
+        return Err(format!(
+            "unknown constraint type `{source}` for `{name}`, expected one of {}",
+            CONSTRAINT_TYPES.join(", ")
+        ));
+    }
+
+    Ok(())
+}
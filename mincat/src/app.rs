@@ -1,24 +1,118 @@
-use http::{Extensions, Request, StatusCode};
+use http::{header, HeaderValue, Method, Request, StatusCode};
+use http_body::Body as _;
 use hyper::{body::Incoming, service::service_fn};
-use hyper_util::{
-    rt::{TokioExecutor, TokioIo},
-    server::conn::auto::Builder,
-};
+use hyper_util::rt::TokioIo;
 use mincat_core::{
     body::Body,
+    handler::{Handler, HandlerChain, HandlerFunc},
+    middleware::Middleware,
     response::{IntoResponse, Response},
-    router::Router,
+    router::{FrozenRouter, RouteError, RouteTable, Router},
+    state::{AppState, NoState},
+};
+use std::{
+    collections::VecDeque, convert::Infallible, future::Future, io, marker::PhantomData,
+    net::SocketAddr, sync::Arc, time::Duration,
+};
+use tokio::{
+    net::TcpListener,
+    sync::{oneshot, watch},
+    task::{JoinHandle, JoinSet},
+};
+
+use crate::{
+    extract::{HostParams, MatchedPath, NestedPath, RawPathParams, UrlFor},
+    listener::{Accept, Listener, Listeners},
+    server::{self, Idle, IdleBody, ServerConfig},
 };
-use std::{convert::Infallible, net::SocketAddr, sync::Arc, time::Duration};
-use tokio::net::TcpListener;
 
-#[derive(Debug, Clone)]
-pub struct MincatRoutePath(pub String);
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[cfg(any(feature = "state", feature = "cookie", feature = "client-ip"))]
+pub(crate) fn get_state<T>(extensions: &http::Extensions) -> Option<&T>
+where
+    T: Send + Sync + 'static,
+{
+    AppState::find::<T>(extensions)
+}
+
+pub struct App<S = NoState> {
+    router: Arc<Router<S>>,
+    middleware: VecDeque<Box<dyn Middleware>>,
+    drain_timeout: Option<Duration>,
+    server_config: ServerConfig,
+    dispatcher: Arc<Dispatcher>,
+    _state: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for App<S> {
+    fn clone(&self) -> Self {
+        Self {
+            router: self.router.clone(),
+            middleware: self.middleware.clone(),
+            drain_timeout: self.drain_timeout,
+            server_config: self.server_config.clone(),
+            dispatcher: self.dispatcher.clone(),
+            _state: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Dispatcher {
+    chain: HandlerChain,
+    state: AppState,
+}
+
+impl Dispatcher {
+    pub(crate) async fn dispatch(&self, mut request: Request<Body>) -> Response {
+        request.extensions_mut().insert(self.state.clone());
+        let is_head = request.method() == Method::HEAD;
+        let response = self.chain.exectue(request).await;
+        if is_head {
+            return strip_body(response);
+        }
+
+        response
+    }
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::with_state_unchecked(AppState::default())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    pub drained: usize,
+    pub force_closed: usize,
+}
+
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    signal: Option<oneshot::Sender<()>>,
+    task: JoinHandle<ShutdownReport>,
+}
+
+impl ServerHandle {
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub async fn shutdown(mut self) -> ShutdownReport {
+        if let Some(signal) = self.signal.take() {
+            let _ = signal.send(());
+        }
+
+        self.task.await.expect("server task failed")
+    }
+}
 
-#[derive(Clone, Default)]
-pub struct App {
-    router: Arc<Router>,
-    state: Extensions,
+impl From<Router> for App {
+    fn from(router: Router) -> Self {
+        App::new().router(router)
+    }
 }
 
 impl App {
@@ -26,68 +120,954 @@ impl App {
         Self::default()
     }
 
-    pub fn router(&mut self, router: Router) -> Self {
+    pub fn with_state<S>(state: S) -> App<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let mut app_state = AppState::default();
+        app_state.insert(state);
+        App::with_state_unchecked(app_state)
+    }
+}
+
+impl<S> App<S> {
+    fn with_state_unchecked(state: AppState) -> Self {
+        let router = Arc::new(Router::default());
+        let middleware = VecDeque::new();
+        let chain = build_chain(&router, &middleware, &state);
+
+        Self {
+            router,
+            middleware,
+            drain_timeout: None,
+            server_config: ServerConfig::default(),
+            dispatcher: Arc::new(Dispatcher { chain, state }),
+            _state: PhantomData,
+        }
+    }
+
+    pub(crate) fn into_untyped(self) -> App {
+        App {
+            router: Arc::new(Router::default()),
+            middleware: self.middleware,
+            drain_timeout: self.drain_timeout,
+            server_config: self.server_config,
+            dispatcher: self.dispatcher,
+            _state: PhantomData,
+        }
+    }
+
+    pub fn router(&mut self, router: Router<S>) -> Self {
         let mut self_router = self.router.clone();
         let self_router = Arc::make_mut(&mut self_router);
         self.router = Arc::new(self_router.merge(router));
+        let dispatcher = Arc::make_mut(&mut self.dispatcher);
+        dispatcher.chain = build_chain(&self.router, &self.middleware, &dispatcher.state);
         self.clone()
     }
 
+    pub fn host(&mut self, pattern: &str, router: Router<S>) -> Self {
+        self.router(Router::default().host(pattern, router))
+    }
+
+    pub fn routes(&self) -> RouteTable {
+        self.router.routes()
+    }
+
     pub fn state<T>(&mut self, state: T) -> Self
     where
         T: Clone + Send + Sync + 'static,
     {
-        self.state.insert(state);
+        let dispatcher = Arc::make_mut(&mut self.dispatcher);
+        dispatcher.state.insert(state);
+        dispatcher.chain = build_chain(&self.router, &self.middleware, &dispatcher.state);
         self.clone()
     }
 
-    pub async fn run(&mut self, addr: &str) {
-        let addr = addr.parse::<SocketAddr>().expect("addr parse failed");
-        let listener = TcpListener::bind(addr).await.expect("tcp bind failed");
+    pub fn middleware<T>(&mut self, middleware: T) -> Self
+    where
+        T: Into<Box<dyn Middleware>>,
+    {
+        self.middleware.push_back(middleware.into());
+        let dispatcher = Arc::make_mut(&mut self.dispatcher);
+        dispatcher.chain = build_chain(&self.router, &self.middleware, &dispatcher.state);
+        self.clone()
+    }
 
-        loop {
-            let (stream, _) = match listener.accept().await {
-                Ok(conn) => conn,
-                Err(e) => {
-                    tracing::error!("accept error: {e}");
-                    tokio::time::sleep(Duration::from_secs(1)).await;
-                    continue;
-                }
+    pub fn drain_timeout(&mut self, timeout: Duration) -> Self {
+        self.drain_timeout = Some(timeout);
+        self.clone()
+    }
+
+    pub fn server_config(&mut self, config: ServerConfig) -> Self {
+        self.server_config = config;
+        self.clone()
+    }
+
+    pub(crate) fn dispatcher(&self) -> Arc<Dispatcher> {
+        self.dispatcher.clone()
+    }
+
+    pub(crate) async fn dispatch(&self, request: Request<Body>) -> Response {
+        self.dispatcher.dispatch(request).await
+    }
+
+    pub async fn run(&mut self, addr: &str) -> io::Result<()> {
+        self.run_with_shutdown(addr, std::future::pending())
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn run_with_shutdown<F>(
+        &mut self,
+        addr: &str,
+        signal: F,
+    ) -> io::Result<ShutdownReport>
+    where
+        F: Future<Output = ()>,
+    {
+        let listener = TcpListener::bind(addr).await?;
+        Ok(accept_loop(self.clone().into_untyped(), listener, signal).await)
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    pub async fn run_tls(&mut self, addr: &str, config: crate::tls::TlsConfig) -> io::Result<()> {
+        let listener = Listener::tls(TcpListener::bind(addr).await?, &config)?;
+        self.serve([listener]).await
+    }
+
+    pub async fn serve<I>(&mut self, listeners: I) -> io::Result<()>
+    where
+        I: IntoIterator,
+        I::Item: Into<Listener>,
+    {
+        self.serve_with_shutdown(listeners, std::future::pending())
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn serve_with_shutdown<I, F>(
+        &mut self,
+        listeners: I,
+        signal: F,
+    ) -> io::Result<ShutdownReport>
+    where
+        I: IntoIterator,
+        I::Item: Into<Listener>,
+        F: Future<Output = ()>,
+    {
+        let listeners = Listeners(listeners.into_iter().map(Into::into).collect());
+        if listeners.0.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "serve requires at least one listener",
+            ));
+        }
+
+        Ok(accept_loop(self.clone().into_untyped(), listeners, signal).await)
+    }
+
+    pub async fn spawn(&mut self, addr: &str) -> io::Result<ServerHandle> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let (signal, receiver) = oneshot::channel();
+        let task = tokio::spawn(accept_loop(self.clone().into_untyped(), listener, async {
+            if receiver.await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }));
+
+        Ok(ServerHandle {
+            local_addr,
+            signal: Some(signal),
+            task,
+        })
+    }
+}
+
+async fn accept_loop<L, F>(app: App, listener: L, signal: F) -> ShutdownReport
+where
+    L: Accept,
+    F: Future<Output = ()>,
+{
+    let (shutdown, shutdown_receiver) = watch::channel(());
+    let mut connections = JoinSet::new();
+    let builder = Arc::new(app.server_config.builder());
+    let server_config = Arc::new(app.server_config.clone());
+    let connection_limit = app.server_config.connection_limit();
+    tokio::pin!(signal);
+
+    loop {
+        let (accepted, permit) = tokio::select! {
+            _ = &mut signal => break,
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            accepted = async {
+                let permit = server::acquire(&connection_limit).await;
+                (listener.accept().await, permit)
+            } => accepted,
+        };
+
+        let connecting = match accepted {
+            Ok(connecting) => connecting,
+            Err(e) => {
+                tracing::error!("accept error: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue;
+            }
+        };
+        let dispatcher = app.dispatcher();
+        let server_config = server_config.clone();
+        let builder = builder.clone();
+        let keep_alive_timeout = app.server_config.keep_alive();
+        let handshake_timeout = app.server_config.handshake_timeout();
+        let mut shutdown_receiver = shutdown_receiver.clone();
+        connections.spawn(async move {
+            let _permit = permit;
+            let (io, info) = tokio::select! {
+                connected = tokio::time::timeout(handshake_timeout, connecting) => match connected {
+                    Ok(Ok((io, info))) => (TokioIo::new(io), info),
+                    Ok(Err(e)) => {
+                        tracing::error!("connection handshake error: {e}");
+                        return;
+                    }
+                    Err(_) => {
+                        tracing::error!("connection handshake timed out");
+                        return;
+                    }
+                },
+                _ = shutdown_receiver.changed() => return,
             };
-            let io = TokioIo::new(stream);
-            let router = self.router.clone();
-            let state = self.state.clone();
-            let service = service_fn(move |mut request| {
-                let router = router.clone();
-                let state = state.clone();
-                request.extensions_mut().extend(state);
-                handler(router, request)
-            });
 
-            tokio::task::spawn(async move {
-                if let Err(e) = Builder::new(TokioExecutor::new())
-                    .serve_connection_with_upgrades(io, service)
-                    .await
-                {
-                    tracing::error!("serve_connection_with_upgrades error: {e}");
+            let idle = Idle::new();
+            let service_idle = idle.clone();
+            let service = service_fn(move |mut request: Request<Incoming>| {
+                let busy = service_idle.busy();
+                request.extensions_mut().insert(info.clone());
+                let header_too_large = server_config.header_too_large(request.headers());
+                let dispatcher = dispatcher.clone();
+                async move {
+                    let response = match header_too_large {
+                        true => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE.into_response(),
+                        false => dispatcher.dispatch(request.map(Body::new)).await,
+                    };
+                    Ok::<_, Infallible>(response.map(|body| Body::new(IdleBody::new(body, busy))))
                 }
             });
+
+            let conn = builder.serve_connection_with_upgrades(io, service);
+            tokio::pin!(conn);
+
+            let result = tokio::select! {
+                result = conn.as_mut() => result,
+                _ = shutdown_receiver.changed() => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+                _ = idle.timeout(keep_alive_timeout) => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+            };
+
+            if let Err(e) = result {
+                tracing::error!("serve_connection_with_upgrades error: {e}");
+            }
+        });
+    }
+
+    drop(listener);
+    let _ = shutdown.send(());
+
+    let mut report = ShutdownReport::default();
+    let drain = async {
+        while connections.join_next().await.is_some() {
+            report.drained += 1;
         }
+    };
+
+    let drain_timeout = app.drain_timeout.unwrap_or(DRAIN_TIMEOUT);
+    if tokio::time::timeout(drain_timeout, drain).await.is_err() {
+        report.force_closed = connections.len();
+        connections.shutdown().await;
+    }
+
+    report
+}
+
+fn build_chain<S>(
+    router: &Router<S>,
+    middleware: &VecDeque<Box<dyn Middleware>>,
+    state: &AppState,
+) -> HandlerChain {
+    Handler {
+        func: Box::new(RouterFunc(Arc::new(router.freeze_with(state)))),
+        middleware: Some(middleware.clone()),
     }
+    .into()
 }
 
-async fn handler(router: Arc<Router>, request: Request<Incoming>) -> Result<Response, Infallible> {
-    let mut request = request.map(Body::new);
-    let path = request.uri().path();
-    let method = request.method();
+#[derive(Clone)]
+struct RouterFunc(Arc<FrozenRouter>);
+
+#[async_trait::async_trait]
+impl HandlerFunc for RouterFunc {
+    async fn call(self: Box<Self>, request: Request<Body>) -> Response {
+        self.call_ref(request).await
+    }
 
-    if let Some((define_path, handler)) = router.get_handler(method, path) {
+    async fn call_ref(&self, mut request: Request<Body>) -> Response {
         request
             .extensions_mut()
-            .insert(MincatRoutePath(define_path));
+            .insert(UrlFor(self.0.names().clone()));
+
+        let (mut parts, body) = request.into_parts();
+        let miss = match self.0.get_handler(&parts) {
+            Ok(matched) => {
+                let params = matched
+                    .params
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                let host_params = matched
+                    .host_params
+                    .iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect();
+                let matched_path = matched.path.clone();
+                let nested_path = matched.nested_path.cloned();
+                let route_state = matched.state.cloned();
+                let chain = matched.chain;
+
+                let extensions = &mut parts.extensions;
+                extensions.insert(MatchedPath(matched_path));
+                extensions.insert(RawPathParams(params));
+                extensions.insert(HostParams(host_params));
+                if let Some(nested_path) = nested_path {
+                    extensions.insert(NestedPath(nested_path));
+                }
+                if let Some(route_state) = route_state {
+                    extensions.insert(route_state);
+                }
+
+                return chain.exectue(Request::from_parts(parts, body)).await;
+            }
+            Err(miss) => miss,
+        };
+
+        let mut request = Request::from_parts(parts, body);
+
+        let error = miss.error;
+        let mut response = match miss.fallback {
+            Some(chain) => {
+                if let Some(nested_path) = miss.nested_path {
+                    request
+                        .extensions_mut()
+                        .insert(NestedPath(nested_path.clone()));
+                }
+
+                chain.exectue(request).await
+            }
+            None => match error {
+                RouteError::NotFound => StatusCode::NOT_FOUND.into_response(),
+                RouteError::MethodNotAllowed(_) => StatusCode::METHOD_NOT_ALLOWED.into_response(),
+                RouteError::Rejected(status) => status.into_response(),
+            },
+        };
+
+        if let Some(allow) = error.allow() {
+            response.headers_mut().entry(header::ALLOW).or_insert(allow);
+        }
+
+        response
+    }
+
+    fn clone_box(&self) -> Box<dyn HandlerFunc> {
+        Box::new(self.clone())
+    }
+}
+
+fn strip_body(response: Response) -> Response {
+    let (mut parts, body) = response.into_parts();
+    if !parts.headers.contains_key(header::CONTENT_LENGTH) {
+        if let Some(len) = body.size_hint().exact() {
+            parts
+                .headers
+                .insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+        }
+    }
+
+    Response::from_parts(parts, Body::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, net::SocketAddr, time::Duration};
+
+    use http::{header, HeaderValue, Method, StatusCode};
+    use mincat_core::body::Body;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    };
+
+    use crate::{
+        http::{
+            connect, get,
+            guard::{Accept, ContentType, Query},
+            post, route, trace, Request, Response, Router,
+        },
+        middleware::{middleware, Next},
+        test::{TestClient, TestResponse},
+    };
+
+    use super::{App, ServerConfig};
+
+    #[get("/")]
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    #[get("/slow")]
+    async fn slow() -> &'static str {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        "slow"
+    }
 
-        return Ok(handler.exectue(request).await);
+    #[get("/stuck")]
+    async fn stuck() -> &'static str {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        "stuck"
     }
 
-    Ok(StatusCode::NOT_FOUND.into_response())
+    #[get("/stream")]
+    async fn chunks() -> Body {
+        Body::from_stream(futures_util::stream::unfold(0, |chunk| async move {
+            if chunk == 4 {
+                return None;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            Some((Ok::<_, Infallible>(format!("chunk{chunk}")), chunk + 1))
+        }))
+    }
+
+    fn app() -> App {
+        App::from(
+            Router::new()
+                .route(index)
+                .route(slow)
+                .route(stuck)
+                .route(chunks),
+        )
+    }
+
+    async fn send(addr: SocketAddr, path: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {path} HTTP/1.1\r\nhost: localhost\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        stream
+    }
+
+    async fn read(mut stream: TcpStream) -> String {
+        let mut buf = vec![0; 1024];
+        let len = stream.read(&mut buf).await.unwrap();
+        String::from_utf8_lossy(&buf[..len]).to_string()
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_in_flight_requests() {
+        let handle = app().spawn("127.0.0.1:0").await.unwrap();
+        let stream = send(handle.local_addr(), "/slow").await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let report = handle.shutdown().await;
+        assert_eq!(report.drained, 1);
+        assert_eq!(report.force_closed, 0);
+
+        let response = read(stream).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("slow"));
+    }
+
+    #[tokio::test]
+    async fn shutdown_force_closes_after_drain_timeout() {
+        let handle = app()
+            .drain_timeout(Duration::from_millis(100))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+        let _stream = send(handle.local_addr(), "/stuck").await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let report = handle.shutdown().await;
+        assert_eq!(report.drained, 0);
+        assert_eq!(report.force_closed, 1);
+    }
+
+    #[tokio::test]
+    async fn dropped_handle_keeps_serving() {
+        let handle = app().spawn("127.0.0.1:0").await.unwrap();
+        let addr = handle.local_addr();
+        drop(handle);
+
+        let response = read(send(addr, "/").await).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("index"));
+    }
+
+    async fn send_with_header(addr: SocketAddr, size: usize) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "GET / HTTP/1.1\r\nhost: localhost\r\nx-big: {}\r\n\r\n",
+            "a".repeat(size)
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        read(stream).await
+    }
+
+    #[tokio::test]
+    async fn oversized_headers_are_rejected_before_dispatch() {
+        let handle = app()
+            .server_config(ServerConfig::new().max_header_size(16 * 1024))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        for (size, status) in [(12 * 1024, "200 OK"), (17 * 1024, "431")] {
+            let response = send_with_header(handle.local_addr(), size).await;
+            assert!(response.starts_with(&format!("HTTP/1.1 {status}")));
+        }
+
+        handle.shutdown().await;
+    }
+
+    #[test]
+    #[should_panic(expected = "max_header_size must be at least 8192 bytes")]
+    fn max_header_size_below_read_buffer_panics() {
+        ServerConfig::new().max_header_size(64);
+    }
+
+    async fn closed(mut stream: TcpStream) -> bool {
+        let mut buf = vec![0; 1024];
+        let read = tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buf)).await;
+        matches!(read, Ok(Ok(0)) | Ok(Err(_)))
+    }
+
+    #[tokio::test]
+    async fn slow_request_head_is_closed_after_header_read_timeout() {
+        let handle = app()
+            .server_config(ServerConfig::new().header_read_timeout(Duration::from_millis(100)))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        let mut stream = TcpStream::connect(handle.local_addr()).await.unwrap();
+        stream.write_all(b"GET / HTTP/1.1\r\n").await.unwrap();
+        assert!(closed(stream).await);
+
+        handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn idle_keep_alive_connection_is_closed() {
+        let handle = app()
+            .server_config(ServerConfig::new().keep_alive_timeout(Duration::from_millis(100)))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        let mut stream = send(handle.local_addr(), "/").await;
+        let mut buf = vec![0; 1024];
+        let len = stream.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).ends_with("index"));
+        assert!(closed(stream).await);
+
+        handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn streaming_response_keeps_the_connection_alive_past_the_timeout() {
+        let handle = app()
+            .server_config(ServerConfig::new().keep_alive_timeout(Duration::from_millis(150)))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        let mut stream = send(handle.local_addr(), "/stream").await;
+        let mut response = Vec::new();
+        let mut buf = vec![0; 1024];
+        while !response.ends_with(b"0\r\n\r\n") {
+            let len = stream.read(&mut buf).await.unwrap();
+            assert_ne!(len, 0, "connection closed while streaming");
+            response.extend_from_slice(&buf[..len]);
+        }
+        assert!(String::from_utf8_lossy(&response).contains("chunk3"));
+
+        let request = "GET / HTTP/1.1\r\nhost: localhost\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let response = read(stream).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("index"));
+
+        handle.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn connections_over_the_limit_wait_for_a_free_slot() {
+        let handle = app()
+            .server_config(ServerConfig::new().max_connections(1))
+            .spawn("127.0.0.1:0")
+            .await
+            .unwrap();
+
+        let mut first = send(handle.local_addr(), "/").await;
+        let mut buf = vec![0; 1024];
+        let len = first.read(&mut buf).await.unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).ends_with("index"));
+
+        let mut second = send(handle.local_addr(), "/").await;
+        let waiting = tokio::time::timeout(Duration::from_millis(200), second.read(&mut buf)).await;
+        assert!(waiting.is_err());
+
+        drop(first);
+        let response = read(second).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("index"));
+
+        handle.shutdown().await;
+    }
+
+    #[get("/items")]
+    async fn list_items() -> &'static str {
+        "items"
+    }
+
+    #[post("/items")]
+    async fn create_item() -> &'static str {
+        "created"
+    }
+
+    #[tokio::test]
+    async fn unregistered_method_is_method_not_allowed() {
+        let client = TestClient::new(Router::new().route(list_items).route(create_item));
+
+        let response = client.delete("/items").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers()[header::ALLOW],
+            "GET, HEAD, OPTIONS, POST"
+        );
+
+        let response = client.delete("/missing").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert!(response.headers().get(header::ALLOW).is_none());
+    }
+
+    #[tokio::test]
+    async fn custom_fallbacks_answer_misses() {
+        let router = Router::new()
+            .route(list_items)
+            .fallback(|| async { (StatusCode::NOT_FOUND, "no such page") })
+            .method_not_allowed_fallback(|| async { "wrong method" });
+        let client = TestClient::new(router);
+
+        let response = client.get("/missing").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.text().await, "no such page");
+
+        let response = client.post("/items").send().await;
+        assert_eq!(response.headers()[header::ALLOW], "GET, HEAD, OPTIONS");
+        assert_eq!(response.text().await, "wrong method");
+    }
+
+    #[tokio::test]
+    async fn head_and_options_are_answered_automatically() {
+        let client = TestClient::new(Router::new().route(list_items).route(create_item));
+
+        let response = client.head("/items").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "5");
+        assert!(response.bytes().await.is_empty());
+
+        let response = client.options("/items").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::ALLOW],
+            "GET, HEAD, OPTIONS, POST"
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_head_and_options_can_be_disabled() {
+        let client = TestClient::new(
+            Router::new()
+                .route(list_items)
+                .auto_head(false)
+                .auto_options(false),
+        );
+
+        let response = client.head("/items").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET");
+
+        let response = client.options("/items").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn merged_router_keeps_its_own_auto_settings() {
+        let legacy = Router::new()
+            .route(create_item)
+            .auto_head(false)
+            .auto_options(false);
+        let router = Router::new()
+            .route(index)
+            .merge(legacy)
+            .group("/v1", Router::new().route(list_items).auto_head(false));
+        let client = TestClient::new(router);
+
+        assert_eq!(client.head("/").send().await.status(), StatusCode::OK);
+        assert_eq!(client.options("/").send().await.status(), StatusCode::OK);
+
+        let response = client.options("/items").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "POST");
+
+        let response = client.head("/v1/items").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(response.headers()[header::ALLOW], "GET, OPTIONS");
+    }
+
+    #[get("/report", guard = Accept::new("application/json"))]
+    async fn report_json() -> &'static str {
+        "json"
+    }
+
+    #[get("/report", guard = Accept::new("text/csv"))]
+    async fn report_csv() -> &'static str {
+        "csv"
+    }
+
+    #[post("/upload", guard = ContentType::new("application/json"))]
+    async fn upload_json() -> &'static str {
+        "json"
+    }
+
+    #[post("/upload", guard = Accept::new("text/plain"))]
+    async fn upload_text() -> &'static str {
+        "text"
+    }
+
+    #[get("/search", guard = Query::new("debug"))]
+    async fn search_debug() -> &'static str {
+        "debug"
+    }
+
+    #[get("/search")]
+    async fn search() -> &'static str {
+        "search"
+    }
+
+    #[tokio::test]
+    async fn guards_select_handler_or_reject() {
+        let client = TestClient::new(
+            Router::new()
+                .route(search)
+                .route(search_debug)
+                .route(report_json)
+                .route(report_csv),
+        );
+
+        let response = client.get("/search").send().await;
+        assert_eq!(response.text().await, "search");
+        let response = client.get("/search?debug").send().await;
+        assert_eq!(response.text().await, "debug");
+
+        let response = client
+            .get("/report")
+            .header(header::ACCEPT, "text/csv;q=0.9, application/xml")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "csv");
+
+        let response = client
+            .get("/report")
+            .header(header::ACCEPT, "application/xml")
+            .send()
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[tokio::test]
+    async fn accept_guards_are_ranked_by_quality() {
+        let client = TestClient::new(Router::new().route(report_json).route(report_csv));
+
+        let response = client
+            .get("/report")
+            .header(header::ACCEPT, "application/json;q=0.5, text/csv")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "csv");
+
+        let response = client
+            .get("/report")
+            .header(header::ACCEPT, "text/*;q=0.8, application/json;q=0.8")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "json");
+
+        let response = client.get("/report").send().await;
+        assert_eq!(response.text().await, "json");
+    }
+
+    #[tokio::test]
+    async fn content_type_guard_rejects_with_415() {
+        let client = TestClient::new(Router::new().route(upload_json));
+
+        let response = client
+            .post("/upload")
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "json");
+
+        let response = client
+            .post("/upload")
+            .header(header::CONTENT_TYPE, "text/plain")
+            .send()
+            .await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+
+    #[tokio::test]
+    async fn first_guard_rejection_is_returned() {
+        let client = TestClient::new(Router::new().route(upload_json).route(upload_text));
+
+        let response = client
+            .post("/upload")
+            .header(header::CONTENT_TYPE, "text/csv")
+            .header(header::ACCEPT, "text/csv")
+            .send()
+            .await;
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let response = client
+            .post("/upload")
+            .header(header::CONTENT_TYPE, "text/csv")
+            .header(header::ACCEPT, "text/plain")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "text");
+    }
+
+    #[middleware]
+    async fn tagged(request: Request, next: Next) -> Response {
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .append("x-tag", HeaderValue::from_static("tagged"));
+        response
+    }
+
+    #[route(GET, POST, PURGE, path = "/cache")]
+    async fn cache(method: Method) -> String {
+        method.to_string()
+    }
+
+    #[trace("/echo")]
+    async fn echo() -> &'static str {
+        "trace"
+    }
+
+    #[connect("/tunnel")]
+    async fn tunnel() -> &'static str {
+        "connect"
+    }
+
+    #[tokio::test]
+    async fn route_macro_registers_every_listed_method() {
+        let client = TestClient::new(Router::new().route(cache));
+
+        for method in ["GET", "POST", "PURGE"] {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            let response = client.request(method.clone(), "/cache").send().await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.text().await, method.as_str());
+        }
+
+        let response = client.delete("/cache").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            response.headers()[header::ALLOW],
+            "GET, HEAD, OPTIONS, POST, PURGE"
+        );
+    }
+
+    #[tokio::test]
+    async fn trace_and_connect_macros_register_their_method() {
+        let client = TestClient::new(Router::new().route(echo).route(tunnel));
+
+        let response = client.request(Method::TRACE, "/echo").send().await;
+        assert_eq!(response.text().await, "trace");
+
+        let response = client.request(Method::CONNECT, "/tunnel").send().await;
+        assert_eq!(response.text().await, "connect");
+
+        let response = client.get("/echo").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[tokio::test]
+    async fn middleware_reaches_every_route_of_a_method_set() {
+        let client = TestClient::new(Router::new().route(cache.middleware(tagged)));
+
+        for method in [Method::GET, Method::POST] {
+            let response = client.request(method, "/cache").send().await;
+            assert_eq!(response.headers()["x-tag"], "tagged");
+        }
+
+        let purge = Method::from_bytes(b"PURGE").unwrap();
+        let response = client.request(purge, "/cache").send().await;
+        assert_eq!(response.headers()["x-tag"], "tagged");
+    }
+
+    #[middleware]
+    async fn audited(request: Request, next: Next) -> Response {
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .append("x-tag", HeaderValue::from_static("audited"));
+        response
+    }
+
+    #[get("/listed", middleware = [tagged, audited])]
+    async fn listed_middleware() -> &'static str {
+        "listed"
+    }
+
+    #[get("/chained")]
+    async fn chained_middleware() -> &'static str {
+        "chained"
+    }
+
+    #[tokio::test]
+    async fn middleware_argument_runs_in_chained_order() {
+        let router = Router::new()
+            .route(listed_middleware)
+            .route(chained_middleware.middleware(tagged).middleware(audited));
+        let client = TestClient::new(router);
+
+        let tags = |response: &TestResponse| {
+            response
+                .headers()
+                .get_all("x-tag")
+                .iter()
+                .map(|value| value.to_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let listed = client.get("/listed").send().await;
+        let chained = client.get("/chained").send().await;
+        assert_eq!(tags(&listed), ["tagged", "audited"]);
+        assert_eq!(tags(&listed), tags(&chained));
+    }
 }
@@ -0,0 +1,175 @@
+use std::net::{IpAddr, SocketAddr};
+
+use http::{header, HeaderMap};
+use ipnet::IpNet;
+use mincat_core::request::{FromRequestParts, Parts};
+
+use crate::app::get_state;
+
+use super::{ConnectInfo, ExtractError};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Vec<IpNet>);
+
+impl TrustedProxies {
+    pub fn new<I, S>(cidrs: I) -> Result<Self, ipnet::AddrParseError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut nets = vec![];
+        for cidr in cidrs {
+            let cidr = cidr.as_ref().trim();
+            let net = match cidr.parse::<IpAddr>() {
+                Ok(ip) => IpNet::from(ip),
+                Err(_) => cidr.parse::<IpNet>()?,
+            };
+            nets.push(net);
+        }
+
+        Ok(Self(nets))
+    }
+
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(ip))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ClientIp {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo>()
+            .and_then(|info| info.peer_addr)
+            .map(|addr| addr.ip())
+            .ok_or(ExtractError("missing connect info".to_string()))?;
+
+        let trusted = match get_state::<TrustedProxies>(&parts.extensions) {
+            Some(trusted) if trusted.contains(&peer_ip) => trusted,
+            _ => return Ok(ClientIp(peer_ip)),
+        };
+
+        let mut client_ip = peer_ip;
+        for hop in forwarded_chain(&parts.headers).into_iter().rev() {
+            match hop {
+                Some(ip) => {
+                    client_ip = ip;
+                    if !trusted.contains(&ip) {
+                        break;
+                    }
+                }
+                None => break,
+            }
+        }
+
+        Ok(ClientIp(client_ip))
+    }
+}
+
+fn forwarded_chain(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    if headers.contains_key(header::FORWARDED) {
+        return headers
+            .get_all(header::FORWARDED)
+            .iter()
+            .flat_map(|value| value.to_str().unwrap_or("").split(','))
+            .map(|element| {
+                element
+                    .split(';')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| key.trim().eq_ignore_ascii_case("for"))
+                    .and_then(|(_, value)| parse_node(value))
+            })
+            .collect();
+    }
+
+    headers
+        .get_all(X_FORWARDED_FOR)
+        .iter()
+        .flat_map(|value| value.to_str().unwrap_or("").split(','))
+        .map(parse_node)
+        .collect()
+}
+
+fn parse_node(node: &str) -> Option<IpAddr> {
+    let node = node.trim().trim_matches('"');
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    if let Ok(addr) = node.parse::<SocketAddr>() {
+        return Some(addr.ip());
+    }
+
+    node.strip_prefix('[')
+        .and_then(|node| node.strip_suffix(']'))
+        .and_then(|node| node.parse::<IpAddr>().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use crate::{
+        extract::ClientIp,
+        http::{get, Router},
+        test::TestClient,
+        App,
+    };
+
+    use super::TrustedProxies;
+
+    #[get("/ip")]
+    async fn client_ip(ClientIp(ip): ClientIp) -> String {
+        ip.to_string()
+    }
+
+    fn client() -> TestClient {
+        let trusted = TrustedProxies::new(["10.0.0.0/8"]).unwrap();
+        TestClient::new(App::from(Router::new().route(client_ip)).state(trusted))
+    }
+
+    fn addr(addr: &str) -> SocketAddr {
+        addr.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn ignores_forwarded_headers_from_untrusted_peers() {
+        let response = client()
+            .get("/ip")
+            .peer_addr(addr("203.0.113.7:4000"))
+            .header("x-forwarded-for", "198.51.100.1")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "203.0.113.7");
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_first_untrusted_hop() {
+        let response = client()
+            .get("/ip")
+            .peer_addr(addr("10.0.0.2:4000"))
+            .header("x-forwarded-for", "198.51.100.1, 192.0.2.9, 10.0.0.1")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "192.0.2.9");
+
+        let response = client()
+            .get("/ip")
+            .peer_addr(addr("10.0.0.2:4000"))
+            .header(
+                "forwarded",
+                "for=192.0.2.60;proto=http, for=\"[2001:db8::1]:80\"",
+            )
+            .send()
+            .await;
+        assert_eq!(response.text().await, "2001:db8::1");
+    }
+}
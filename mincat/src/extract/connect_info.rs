@@ -0,0 +1,32 @@
+use std::net::SocketAddr;
+
+use mincat_core::request::{FromRequestParts, Parts};
+
+use super::ExtractError;
+
+#[derive(Debug, Clone, Default)]
+pub struct ConnectInfo {
+    pub peer_addr: Option<SocketAddr>,
+    pub local_addr: Option<SocketAddr>,
+    pub tls: Option<TlsInfo>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TlsInfo {
+    pub server_name: Option<String>,
+    pub alpn_protocol: Option<String>,
+    pub protocol_version: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for ConnectInfo {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<ConnectInfo>()
+            .cloned()
+            .ok_or(ExtractError("missing connect info".to_string()))
+    }
+}
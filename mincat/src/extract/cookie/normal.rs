@@ -40,7 +40,7 @@ impl CookieJar {
 }
 
 #[async_trait::async_trait]
-impl FromRequestParts for CookieJar {
+impl<S> FromRequestParts<S> for CookieJar {
     type Error = Infallible;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
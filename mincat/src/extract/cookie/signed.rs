@@ -6,6 +6,8 @@ use mincat_core::{
     response::{IntoResponse, IntoResponseParts, Response},
 };
 
+use crate::app::get_state;
+
 use super::{cookies_from_request, set_cookies, CookieKey};
 
 pub struct SignedCookieJar {
@@ -14,13 +16,11 @@ pub struct SignedCookieJar {
 }
 
 #[async_trait::async_trait]
-impl FromRequestParts for SignedCookieJar {
+impl<S> FromRequestParts<S> for SignedCookieJar {
     type Error = Error;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
-        let CookieKey(key) = parts
-            .extensions
-            .get::<CookieKey>()
+        let CookieKey(key) = get_state::<CookieKey>(&parts.extensions)
             .ok_or(Error::new("missing state Cookiekey"))?;
 
         Ok(SignedCookieJar::from_headers(&parts.headers, key))
@@ -36,7 +36,7 @@ where
     T: FromMultipart + FromMultipartNull;
 
 #[async_trait::async_trait]
-impl<T> FromRequest for FormData<T>
+impl<S, T> FromRequest<S> for FormData<T>
 where
     T: FromMultipart + FromMultipartNull + 'static,
 {
@@ -11,7 +11,7 @@ use super::ExtractError;
 pub struct FormUrlencoded<T>(pub T);
 
 #[async_trait::async_trait]
-impl<T> FromRequest for FormUrlencoded<T>
+impl<S, T> FromRequest<S> for FormUrlencoded<T>
 where
     T: DeserializeOwned + Clone + Send + 'static,
 {
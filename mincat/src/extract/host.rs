@@ -0,0 +1,87 @@
+use mincat_core::request::{FromRequestParts, Parts};
+
+use super::ExtractError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostParams(pub(crate) Vec<(String, String)>);
+
+impl HostParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for HostParams {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<HostParams>()
+            .cloned()
+            .ok_or(ExtractError("missing host params".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{header, StatusCode};
+
+    use crate::{
+        http::{get, Router},
+        test::TestClient,
+        App,
+    };
+
+    use super::HostParams;
+
+    #[get("/")]
+    async fn home() -> &'static str {
+        "home"
+    }
+
+    #[get("/")]
+    async fn api() -> &'static str {
+        "api"
+    }
+
+    #[get("/")]
+    async fn tenant(host: HostParams) -> String {
+        format!("tenant {}", host.get("tenant").unwrap_or_default())
+    }
+
+    #[get("/host")]
+    async fn host_params(host: HostParams) -> String {
+        format!("{:?}", host.iter().collect::<Vec<_>>())
+    }
+
+    #[tokio::test]
+    async fn routes_by_host_and_captures_labels() {
+        let app = App::new()
+            .router(Router::new().route(home).route(host_params))
+            .host("api.localhost", Router::new().route(api))
+            .host(":tenant.localhost", Router::new().route(tenant));
+        let client = TestClient::new(app);
+
+        let get =
+            |host: &'static str, path: &str| client.get(path).header(header::HOST, host).send();
+
+        assert_eq!(get("api.localhost:3000", "/").await.text().await, "api");
+        assert_eq!(get("acme.localhost", "/").await.text().await, "tenant acme");
+        assert_eq!(get("localhost", "/").await.text().await, "home");
+        assert_eq!(get("localhost", "/host").await.text().await, "[]");
+
+        let response = get("acme.localhost", "/host").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -12,7 +12,7 @@ use super::ExtractError;
 pub struct Json<T>(pub T);
 
 #[async_trait::async_trait]
-impl<T> FromRequest for Json<T>
+impl<S, T> FromRequest<S> for Json<T>
 where
     T: DeserializeOwned + Clone + Send + 'static,
 {
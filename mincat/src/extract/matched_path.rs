@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use mincat_core::request::{FromRequestParts, Parts};
+use percent_encoding::percent_decode_str;
+
+use super::ExtractError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchedPath(pub(crate) Arc<str>);
+
+impl MatchedPath {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for MatchedPath {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<MatchedPath>()
+            .cloned()
+            .ok_or(ExtractError("missing matched path".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedPath(pub(crate) Arc<str>);
+
+impl NestedPath {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for NestedPath {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<NestedPath>()
+            .cloned()
+            .ok_or(ExtractError("missing nested path".to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RawPathParams(pub(crate) Vec<(String, String)>);
+
+impl RawPathParams {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn decoded(&self, name: &str) -> Result<Option<String>, ExtractError> {
+        self.get(name).map(decode).transpose()
+    }
+
+    pub fn decode_all(&self) -> Result<Vec<(String, String)>, ExtractError> {
+        self.iter()
+            .map(|(key, value)| Ok((key.to_string(), decode(value)?)))
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for RawPathParams {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<RawPathParams>()
+            .cloned()
+            .ok_or(ExtractError("missing path params".to_string()))
+    }
+}
+
+fn decode(value: &str) -> Result<String, ExtractError> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .map(|value| value.into_owned())
+        .map_err(ExtractError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{
+        http::{get, Router},
+        test::TestClient,
+    };
+
+    use super::{MatchedPath, NestedPath, RawPathParams};
+
+    #[get("/")]
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    #[get("/users")]
+    async fn users(nested: NestedPath) -> String {
+        format!("users under {}", nested.as_str())
+    }
+
+    #[get("/users/:id")]
+    async fn user(params: RawPathParams, matched: MatchedPath) -> String {
+        let id = params.get("id").unwrap_or_default();
+        format!("user {id} at {}", matched.as_str())
+    }
+
+    async fn api_not_found(nested: NestedPath) -> (StatusCode, String) {
+        (
+            StatusCode::NOT_FOUND,
+            format!("no route under {}", nested.as_str()),
+        )
+    }
+
+    fn client() -> TestClient {
+        let api = Router::new()
+            .nest("/v1", Router::new().route(users).route(user))
+            .fallback(api_not_found);
+        TestClient::new(Router::new().route(index).nest("/api", api))
+    }
+
+    #[tokio::test]
+    async fn nested_routes_see_the_prefix_and_full_pattern() {
+        let client = client();
+        assert_eq!(
+            client.get("/api/v1/users").send().await.text().await,
+            "users under /api/v1"
+        );
+        assert_eq!(
+            client.get("/api/v1/users/7").send().await.text().await,
+            "user 7 at /api/v1/users/:id"
+        );
+    }
+
+    #[tokio::test]
+    async fn nested_fallback_only_answers_below_its_prefix() {
+        let client = client();
+
+        let response = client.get("/api/v2/users").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.text().await, "no route under /api");
+
+        let response = client.get("/other").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.text().await, "");
+
+        let response = client.get("/apiv1/users").send().await;
+        assert_eq!(response.text().await, "");
+    }
+}
@@ -1,8 +1,13 @@
+#[cfg(feature = "client-ip")]
+mod client_ip;
+#[cfg(feature = "client-ip")]
+pub use client_ip::{ClientIp, TrustedProxies};
+
 #[cfg(feature = "cookie")]
 pub mod cookie;
 
 #[cfg(feature = "form")]
-mod form_data;
+pub(crate) mod form_data;
 #[cfg(feature = "form")]
 mod form_urlencoded;
 #[cfg(feature = "form")]
@@ -14,6 +19,19 @@ pub mod form {
     pub use multer_derive::{Error, FormContext, FormFile, FromMultipart, MultipartForm};
 }
 
+mod connect_info;
+pub use connect_info::{ConnectInfo, TlsInfo};
+
+mod host;
+pub use host::HostParams;
+
+mod matched_path;
+pub use matched_path::{MatchedPath, NestedPath, RawPathParams};
+
+mod url_for;
+pub use mincat_core::url_for::UrlForError;
+pub use url_for::UrlFor;
+
 use http::StatusCode;
 use mincat_core::response::{IntoResponse, Response};
 use std::{error::Error, fmt::Display};
@@ -26,6 +44,10 @@ pub use json::Json;
 #[cfg(feature = "path")]
 mod path;
 #[cfg(feature = "path")]
+pub use mincat_core::route::{PathField, PathFields};
+#[cfg(feature = "path")]
+pub use mincat_macro::PathFields;
+#[cfg(feature = "path")]
 pub use path::Path;
 
 #[cfg(feature = "query")]
@@ -41,6 +63,8 @@ pub use session::Session;
 #[cfg(feature = "state")]
 mod state;
 #[cfg(feature = "state")]
+pub use mincat_core::state::FromRef;
+#[cfg(feature = "state")]
 pub use state::State;
 
 #[cfg(feature = "websocket")]
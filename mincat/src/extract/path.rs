@@ -1,62 +1,41 @@
-use std::{any::type_name, slice::Iter};
+use std::{any::type_name, fmt::Display, slice::Iter};
 
-use mincat_core::request::{FromRequestParts, Parts};
+use mincat_core::{
+    request::{FromRequestParts, Parts},
+    route::PathParams,
+};
 use serde::{
-    de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    de::{
+        value::StrDeserializer, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor,
+    },
     forward_to_deserialize_any, Deserializer,
 };
 
-use crate::app::MincatRoutePath;
-
-use super::ExtractError;
-
-fn get_path_args(path: &str) -> Vec<String> {
-    let mut res = vec![];
-    path.split('/').for_each(|item| {
-        if let Some(key) = item.strip_prefix(':') {
-            res.push(key.into());
-        }
-    });
-
-    res
-}
-
-fn get_path_args_tuple(
-    define_path: &str,
-    real_path: &str,
-) -> Result<Vec<(String, String)>, ExtractError> {
-    let mut res = vec![];
-    let mut router = matchit::Router::new();
-    router
-        .insert(define_path, true)
-        .map_err(ExtractError::from)?;
-    let matched = router.at(real_path).map_err(ExtractError::from)?;
-    let path_args = get_path_args(define_path);
-
-    for arg in path_args {
-        let value = matched.params.get(&arg).unwrap_or("");
-        res.push((arg, value.to_string()));
-    }
-
-    Ok(res)
-}
+use super::{ExtractError, RawPathParams};
 
+/// Extracts the percent-decoded parameters of the matched route.
+///
+/// The route macros check `Path` against their pattern at compile time.
+/// A tuple needs one value per parameter, and a struct needs
+/// `#[derive(PathFields)]`. Parameters added by a group prefix count only
+/// when the route declares them, as in
+/// `#[get("/posts/:id", prefix = "/blogs/:slug")]`. The full pattern is
+/// checked again when the router is frozen, which panics on a mismatch.
 pub struct Path<T>(pub T);
 
 #[async_trait::async_trait]
-impl<T> FromRequestParts for Path<T>
+impl<S, T> FromRequestParts<S> for Path<T>
 where
     T: DeserializeOwned + Clone + Send + 'static,
 {
     type Error = ExtractError;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
-        let MincatRoutePath(path) = parts
+        let path_args = parts
             .extensions
-            .get::<MincatRoutePath>()
-            .ok_or(ExtractError("missing path".to_string()))?;
-
-        let path_args = get_path_args_tuple(path, parts.uri.path())?;
+            .get::<RawPathParams>()
+            .ok_or(ExtractError("missing path".to_string()))?
+            .decode_all()?;
 
         Ok(Path(T::deserialize(PathDeserializer(path_args.iter()))?))
     }
@@ -316,3 +295,332 @@ impl<'de> Deserializer<'de> for KeyDeserializer<'de> {
         tuple_struct map newtype_struct struct enum ignored_any
     }
 }
+
+impl<T> PathParams for Path<T>
+where
+    T: DeserializeOwned,
+{
+    fn check_params(params: &[&str]) -> Result<(), String> {
+        match T::deserialize(ProbeDeserializer(params)) {
+            Err(ProbeError::Arity(len)) => Err(format!(
+                "Path extracts {len} value(s) but the route pattern has {} parameter(s): [{}]",
+                params.len(),
+                params.join(", ")
+            )),
+            Err(ProbeError::Missing(field)) => Err(format!(
+                "field `{field}` is not a parameter of the route pattern"
+            )),
+            Err(ProbeError::Unknown(param)) => Err(format!(
+                "route parameter `{param}` is not a field of the extractor"
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum ProbeError {
+    Arity(usize),
+    Missing(&'static str),
+    Unknown(String),
+    Other,
+}
+
+impl Display for ProbeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProbeError::Arity(len) => write!(f, "invalid length {len}"),
+            ProbeError::Missing(field) => write!(f, "missing field `{field}`"),
+            ProbeError::Unknown(field) => write!(f, "unknown field `{field}`"),
+            ProbeError::Other => write!(f, "unsupported"),
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+impl serde::de::Error for ProbeError {
+    fn custom<T>(_: T) -> Self
+    where
+        T: Display,
+    {
+        ProbeError::Other
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        ProbeError::Missing(field)
+    }
+
+    fn unknown_field(field: &str, _: &'static [&'static str]) -> Self {
+        ProbeError::Unknown(field.to_string())
+    }
+}
+
+struct ProbeDeserializer<'a>(&'a [&'a str]);
+
+impl<'de, 'a> Deserializer<'de> for ProbeDeserializer<'a> {
+    type Error = ProbeError;
+
+    fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(ProbeError::Other)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_map(ProbeMap(self.0.iter()))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_tuple<V>(self, len: usize, _: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match len == self.0.len() {
+            true => Err(ProbeError::Other),
+            false => Err(ProbeError::Arity(len)),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq
+        tuple_struct enum identifier ignored_any
+    }
+}
+
+struct ProbeMap<'a>(Iter<'a, &'a str>);
+
+impl<'de, 'a> MapAccess<'de> for ProbeMap<'a> {
+    type Error = ProbeError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.0.next() {
+            Some(param) => seed.deserialize(StrDeserializer::new(param)).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(ProbeValue)
+    }
+}
+
+macro_rules! probe_value {
+    ($trait_fn:ident, $visit_fn:ident, $value:expr) => {
+        fn $trait_fn<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.$visit_fn($value)
+        }
+    };
+}
+
+struct ProbeValue;
+
+impl<'de> Deserializer<'de> for ProbeValue {
+    type Error = ProbeError;
+
+    fn deserialize_any<V>(self, _: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(ProbeError::Other)
+    }
+
+    probe_value!(deserialize_bool, visit_bool, false);
+    probe_value!(deserialize_i8, visit_i8, 0);
+    probe_value!(deserialize_i16, visit_i16, 0);
+    probe_value!(deserialize_i32, visit_i32, 0);
+    probe_value!(deserialize_i64, visit_i64, 0);
+    probe_value!(deserialize_i128, visit_i128, 0);
+    probe_value!(deserialize_u8, visit_u8, 0);
+    probe_value!(deserialize_u16, visit_u16, 0);
+    probe_value!(deserialize_u32, visit_u32, 0);
+    probe_value!(deserialize_u64, visit_u64, 0);
+    probe_value!(deserialize_u128, visit_u128, 0);
+    probe_value!(deserialize_f32, visit_f32, 0.0);
+    probe_value!(deserialize_f64, visit_f64, 0.0);
+    probe_value!(deserialize_char, visit_char, '0');
+    probe_value!(deserialize_string, visit_string, "0".to_string());
+    probe_value!(deserialize_byte_buf, visit_string, "0".to_string());
+    probe_value!(deserialize_ignored_any, visit_bool, false);
+
+    forward_to_deserialize_any! {
+        str bytes option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde::Deserialize;
+
+    use crate::{
+        extract::{MatchedPath, PathFields},
+        http::{get, Router},
+        test::TestClient,
+    };
+
+    use super::Path;
+
+    #[derive(Clone, Deserialize, PathFields)]
+    struct Member {
+        #[serde(rename = "id")]
+        member_id: u32,
+        name: String,
+        #[serde(default)]
+        role: Option<String>,
+    }
+
+    #[get("/teams/:id/members/:name")]
+    async fn team_member(Path(member): Path<Member>) -> String {
+        format!(
+            "{} {} {}",
+            member.member_id,
+            member.name,
+            member.role.as_deref().unwrap_or("none")
+        )
+    }
+
+    #[tokio::test]
+    async fn struct_fields_follow_serde_attributes() {
+        let client = TestClient::new(Router::new().route(team_member));
+
+        let response = client.get("/teams/7/members/ann").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.text().await, "7 ann none");
+
+        let response = client.get("/teams/x/members/ann").send().await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[derive(Clone, Deserialize, PathFields)]
+    struct ById {
+        id: u32,
+    }
+
+    #[derive(Clone, Deserialize, PathFields)]
+    struct ByName {
+        name: String,
+    }
+
+    #[get("/pets/:id<u32>")]
+    async fn pet_by_id(Path(pet): Path<ById>, MatchedPath(path): MatchedPath) -> String {
+        format!("{path} {}", pet.id)
+    }
+
+    #[get("/pets/:name<[a-z]+>")]
+    async fn pet_by_name(Path(pet): Path<ByName>, MatchedPath(path): MatchedPath) -> String {
+        format!("{path} {}", pet.name)
+    }
+
+    #[tokio::test]
+    async fn sibling_constrained_routes_extract_their_own_params() {
+        let client = TestClient::new(Router::new().route(pet_by_id).route(pet_by_name));
+
+        let response = client.get("/pets/3").send().await;
+        assert_eq!(response.text().await, "/pets/:id 3");
+
+        let response = client.get("/pets/rex").send().await;
+        assert_eq!(response.text().await, "/pets/:name rex");
+
+        let response = client.get("/pets/Rex").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[derive(Clone, Deserialize, PathFields)]
+    struct Post {
+        id: u32,
+        slug: String,
+    }
+
+    #[get("/posts/:id", prefix = "/blogs/:slug")]
+    async fn post_by_id(Path(post): Path<Post>) -> String {
+        format!("{} {}", post.id, post.slug)
+    }
+
+    #[test]
+    #[should_panic(expected = "field `slug` is not a parameter of the route pattern")]
+    fn missing_struct_field_is_rejected() {
+        Router::new().route(post_by_id).freeze();
+    }
+
+    #[test]
+    #[should_panic(expected = "field `slug` is not a parameter of the route pattern")]
+    fn mismatched_group_prefix_is_rejected() {
+        let blog = Router::new().route(post_by_id);
+        Router::new().group("/blogs/:name", blog).freeze();
+    }
+
+    #[tokio::test]
+    async fn group_params_count_as_struct_fields() {
+        let router = Router::new().group("/blogs/:slug", Router::new().route(post_by_id));
+        let client = TestClient::new(router);
+        let response = client.get("/blogs/hello/posts/3").send().await;
+        assert_eq!(response.text().await, "3 hello");
+    }
+
+    #[get("/posts/:id<u32>", prefix = "/blogs/:slug")]
+    async fn post_in_blog(Path((slug, id)): Path<(String, u32)>) -> String {
+        format!("{id} {slug}")
+    }
+
+    #[tokio::test]
+    async fn group_params_count_as_tuple_values() {
+        let router = Router::new().group("/blogs/:slug", Router::new().route(post_in_blog));
+        let client = TestClient::new(router);
+        let response = client.get("/blogs/hello/posts/3").send().await;
+        assert_eq!(response.text().await, "3 hello");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Path extracts 2 value(s) but the route pattern has 1 parameter(s): [id]"
+    )]
+    fn extra_tuple_values_are_rejected() {
+        Router::new().route(post_in_blog).freeze();
+    }
+
+    #[get("/users/:name")]
+    async fn user_name(Path((name,)): Path<(String,)>) -> String {
+        name
+    }
+
+    #[tokio::test]
+    async fn params_are_percent_decoded() {
+        let client = TestClient::new(Router::new().route(user_name));
+
+        let response = client.get("/users/a%20b").send().await;
+        assert_eq!(response.text().await, "a b");
+
+        let response = client.get("/users/%E2%9C%93").send().await;
+        assert_eq!(response.text().await, "\u{2713}");
+
+        let response = client.get("/users/%FF").send().await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -6,7 +6,7 @@ use super::ExtractError;
 pub struct Query<T>(pub T);
 
 #[async_trait::async_trait]
-impl<T> FromRequestParts for Query<T>
+impl<S, T> FromRequestParts<S> for Query<T>
 where
     T: DeserializeOwned + Clone + Send + 'static,
 {
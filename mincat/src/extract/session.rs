@@ -39,7 +39,7 @@ impl Session {
 }
 
 #[async_trait::async_trait]
-impl FromRequestParts for Session {
+impl<S> FromRequestParts<S> for Session {
     type Error = Error;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
@@ -1,25 +1,177 @@
 use std::any::type_name;
 
-use mincat_core::request::{FromRequestParts, Parts};
+use mincat_core::{
+    request::{FromRequestParts, Parts},
+    state::{AppState, ProvideState},
+};
+
+use crate::app::get_state;
 
 use super::ExtractError;
 
+/// Extracts a value from the app state.
+///
+/// On a typed `Router<S>` the value is `S` or a `FromRef<S>` sub-state,
+/// resolved once per route when the router is frozen. The value is cloned for every request. State that is expensive to clone
+/// should be stored behind an `Arc` and extracted as `State<Arc<T>>`.
 pub struct State<T>(pub T);
 
 #[async_trait::async_trait]
-impl<T> FromRequestParts for State<T>
+impl<S, T> FromRequestParts<S> for State<T>
 where
+    S: ProvideState<T>,
     T: Clone + Send + Sync + 'static,
 {
     type Error = ExtractError;
 
+    fn provide_state(state: &S, route_state: &mut AppState) {
+        state.provide(route_state);
+    }
+
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
-        let state = parts
-            .extensions
-            .get::<T>()
+        let state = get_state::<T>(&parts.extensions)
             .ok_or(ExtractError(format!("missing state: {}", type_name::<T>())))?
             .clone();
 
         Ok(State(state))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use http::StatusCode;
+
+    use crate::{
+        extract::FromRef,
+        http::{get, IntoRoute, Route, Router},
+        test::TestClient,
+        App,
+    };
+
+    use super::State;
+
+    #[derive(Clone)]
+    struct AppState {
+        name: &'static str,
+        counter: Counter,
+    }
+
+    #[derive(Clone, Default)]
+    struct Counter(Arc<AtomicUsize>);
+
+    impl FromRef<AppState> for Counter {
+        fn from_ref(state: &AppState) -> Self {
+            state.counter.clone()
+        }
+    }
+
+    #[get("/name")]
+    async fn name(State(state): State<AppState>) -> &'static str {
+        state.name
+    }
+
+    #[get("/count")]
+    async fn count(State(Counter(counter)): State<Counter>) -> String {
+        (counter.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+    }
+
+    #[tokio::test]
+    async fn typed_router_provides_state_and_sub_states() {
+        let state = AppState {
+            name: "mincat",
+            counter: Counter::default(),
+        };
+        let router = Router::<AppState>::default().route(name).route(count);
+        let client = TestClient::new(App::with_state(state).router(router));
+
+        assert_eq!(client.get("/name").send().await.text().await, "mincat");
+        assert_eq!(client.get("/count").send().await.text().await, "1");
+        assert_eq!(client.get("/count").send().await.text().await, "2");
+    }
+
+    static VISITS_RESOLVED: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Clone)]
+    struct Visits(Arc<AtomicUsize>);
+
+    impl FromRef<AppState> for Visits {
+        fn from_ref(state: &AppState) -> Self {
+            VISITS_RESOLVED.fetch_add(1, Ordering::SeqCst);
+            Visits(state.counter.0.clone())
+        }
+    }
+
+    #[get("/visits")]
+    async fn visits(State(Visits(total)): State<Visits>) -> String {
+        (total.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+    }
+
+    #[tokio::test]
+    async fn sub_states_are_resolved_once_when_the_router_is_frozen() {
+        let state = AppState {
+            name: "mincat",
+            counter: Counter::default(),
+        };
+        let router = Router::<AppState>::default().route(visits);
+        assert_eq!(router.routes().iter().next().unwrap().middleware, 0);
+
+        let client = TestClient::new(App::with_state(state).router(router));
+        for expected in ["1", "2", "3"] {
+            assert_eq!(client.get("/visits").send().await.text().await, expected);
+        }
+        assert_eq!(VISITS_RESOLVED.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn typed_router_accepts_plain_routes() {
+        let state = AppState {
+            name: "mincat",
+            counter: Counter::default(),
+        };
+        let router = Router::<AppState>::default()
+            .route(Route::from(name))
+            .route(IntoRoute::<AppState, _>::into_routes(count));
+        let client = TestClient::new(App::with_state(state).router(router));
+
+        assert_eq!(client.get("/name").send().await.text().await, "mincat");
+        assert_eq!(client.get("/count").send().await.text().await, "1");
+    }
+
+    struct Config {
+        name: String,
+    }
+
+    #[get("/config")]
+    async fn show_config(State(config): State<Arc<Config>>) -> String {
+        format!("{} {}", config.name, Arc::strong_count(&config))
+    }
+
+    #[tokio::test]
+    async fn arc_state_is_shared_instead_of_cloned() {
+        let state = Arc::new(Config {
+            name: "mincat".to_string(),
+        });
+        let router = Router::<Arc<Config>>::default().route(show_config);
+        let client = TestClient::new(App::with_state(state.clone()).router(router));
+
+        let expected = format!("mincat {}", Arc::strong_count(&state) + 1);
+        assert_eq!(client.get("/config").send().await.text().await, expected);
+        assert_eq!(client.get("/config").send().await.text().await, expected);
+    }
+
+    #[tokio::test]
+    async fn untyped_router_reads_app_state() {
+        let router = Router::new().route(count);
+        let client = TestClient::new(App::from(router).state(Counter::default()));
+        assert_eq!(client.get("/count").send().await.text().await, "1");
+
+        let client = TestClient::new(Router::new().route(count));
+        let response = client.get("/count").send().await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
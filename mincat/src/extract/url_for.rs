@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use mincat_core::{
+    request::{FromRequestParts, Parts},
+    url_for::{RouteNames, UrlForError},
+};
+
+use super::ExtractError;
+
+#[derive(Debug, Clone)]
+pub struct UrlFor(pub(crate) Arc<RouteNames>);
+
+impl UrlFor {
+    pub fn url_for<I, K, V>(&self, name: &str, params: I) -> Result<String, UrlForError>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        self.0.url_for(name, params)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for UrlFor {
+    type Error = ExtractError;
+
+    async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+        parts
+            .extensions
+            .get::<UrlFor>()
+            .cloned()
+            .ok_or(ExtractError("missing route names".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::header;
+
+    use crate::{
+        http::{get, Router},
+        test::TestClient,
+        App,
+    };
+
+    use super::UrlFor;
+
+    #[get("/")]
+    async fn index(url: UrlFor) -> String {
+        url.url_for("user", [("id", "7")]).unwrap()
+    }
+
+    #[get("/users/:id", name = "user")]
+    async fn user() -> &'static str {
+        "user"
+    }
+
+    #[tokio::test]
+    async fn builds_urls_with_group_and_nest_prefixes() {
+        let router = Router::new().route(index).nest(
+            "/api",
+            Router::new().group("/v1", Router::new().route(user)),
+        );
+        assert_eq!(
+            router.url_for("user", [("id", "7")]).unwrap(),
+            "/api/v1/users/7"
+        );
+
+        let client = TestClient::new(router);
+        assert_eq!(client.get("/").send().await.text().await, "/api/v1/users/7");
+    }
+
+    #[get("/tenants/:tenant", name = "tenant")]
+    async fn tenant(url: UrlFor) -> String {
+        url.url_for("tenant", [("tenant", "acme")]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn builds_urls_for_routes_under_host_routers() {
+        let router = Router::new()
+            .route(index)
+            .host("api.localhost", Router::new().route(user));
+        assert_eq!(router.url_for("user", [("id", "7")]).unwrap(), "/users/7");
+
+        let merged = Router::new().merge(router);
+        assert_eq!(merged.url_for("user", [("id", "7")]).unwrap(), "/users/7");
+
+        let app = App::new().host(":tenant.localhost", Router::new().route(tenant));
+        let client = TestClient::new(app);
+        let response = client
+            .get("/tenants/x")
+            .header(header::HOST, "acme.localhost")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "/tenants/acme");
+    }
+}
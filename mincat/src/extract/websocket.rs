@@ -175,7 +175,7 @@ impl WebSocketUpgrade {
 }
 
 #[async_trait::async_trait]
-impl FromRequestParts for WebSocketUpgrade {
+impl<S> FromRequestParts<S> for WebSocketUpgrade {
     type Error = Error;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
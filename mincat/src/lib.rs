@@ -1,10 +1,25 @@
-use app::App;
+#[cfg(test)]
+extern crate self as mincat;
+
+pub use app::{App, ServerHandle, ShutdownReport};
+pub use listener::Listener;
+pub use mincat_core::state::AppState;
+pub use server::ServerConfig;
 
 pub(crate) mod app;
 pub mod extract;
+mod listener;
 pub mod middleware;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 pub mod response;
 pub mod route;
+mod server;
+pub mod test;
+#[cfg(feature = "tls-rustls")]
+pub mod tls;
+#[cfg(feature = "tower")]
+pub mod tower;
 
 pub mod http {
     pub mod header {
@@ -16,15 +31,25 @@ pub mod http {
         pub use mime::*;
     }
 
+    pub mod guard {
+        pub use mincat_core::guard::*;
+    }
+
     pub use http::{Method, StatusCode};
+    #[doc(hidden)]
+    pub use mincat_core::route::__private;
     pub use mincat_core::{
         body::Body,
-        request::{FromRequest, FromRequestParts, Parts, Request},
+        guard::{Guard, Guards},
+        request::{FromRequest, FromRequestParts, Parts, Request, ViaParts, ViaRequest},
         response::{IntoResponse, Response},
-        route::Route,
-        router::Router,
+        route::{IntoRoute, PathParams, Route, WithMiddleware},
+        router::{RouteInfo, RouteTable, Router},
+        state::{NoState, ProvideState},
+    };
+    pub use mincat_macro::{
+        any, connect, delete, get, head, options, patch, post, put, route, trace,
     };
-    pub use mincat_macro::{delete, get, head, options, patch, post, put};
 }
 
 pub fn router(router: crate::http::Router) -> App {
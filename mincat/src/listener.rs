@@ -0,0 +1,231 @@
+use std::{future::Future, io, pin::Pin};
+
+use futures_util::future::select_all;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, ToSocketAddrs},
+};
+
+use crate::extract::ConnectInfo;
+
+pub(crate) trait Io: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+impl<T> Io for T where T: AsyncRead + AsyncWrite + Unpin + Send + 'static {}
+
+pub(crate) type Connecting =
+    Pin<Box<dyn Future<Output = io::Result<(Box<dyn Io>, ConnectInfo)>> + Send>>;
+
+#[async_trait::async_trait]
+pub(crate) trait Accept: Send + Sync {
+    async fn accept(&self) -> io::Result<Connecting>;
+}
+
+#[async_trait::async_trait]
+impl Accept for TcpListener {
+    async fn accept(&self) -> io::Result<Connecting> {
+        let (stream, peer_addr) = TcpListener::accept(self).await?;
+        let info = ConnectInfo {
+            peer_addr: Some(peer_addr),
+            local_addr: stream.local_addr().ok(),
+            tls: None,
+        };
+
+        Ok(Box::pin(async move {
+            Ok((Box::new(stream) as Box<dyn Io>, info))
+        }))
+    }
+}
+
+#[cfg(unix)]
+#[async_trait::async_trait]
+impl Accept for tokio::net::UnixListener {
+    async fn accept(&self) -> io::Result<Connecting> {
+        let (stream, _) = tokio::net::UnixListener::accept(self).await?;
+        let info = ConnectInfo::default();
+
+        Ok(Box::pin(async move {
+            Ok((Box::new(stream) as Box<dyn Io>, info))
+        }))
+    }
+}
+
+pub struct Listener(Box<dyn Accept>);
+
+impl Listener {
+    pub async fn bind<A>(addr: A) -> io::Result<Self>
+    where
+        A: ToSocketAddrs,
+    {
+        Ok(TcpListener::bind(addr).await?.into())
+    }
+
+    #[cfg(unix)]
+    pub fn bind_unix<P>(path: P) -> io::Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        Ok(tokio::net::UnixListener::bind(path)?.into())
+    }
+
+    #[cfg(feature = "tls-rustls")]
+    pub fn tls(listener: TcpListener, config: &crate::tls::TlsConfig) -> io::Result<Self> {
+        Ok(Self(Box::new(crate::tls::TlsListener::new(
+            listener, config,
+        )?)))
+    }
+}
+
+impl From<TcpListener> for Listener {
+    fn from(listener: TcpListener) -> Self {
+        Self(Box::new(listener))
+    }
+}
+
+impl TryFrom<std::net::TcpListener> for Listener {
+    type Error = io::Error;
+
+    fn try_from(listener: std::net::TcpListener) -> Result<Self, Self::Error> {
+        listener.set_nonblocking(true)?;
+        Ok(TcpListener::from_std(listener)?.into())
+    }
+}
+
+#[cfg(unix)]
+impl From<tokio::net::UnixListener> for Listener {
+    fn from(listener: tokio::net::UnixListener) -> Self {
+        Self(Box::new(listener))
+    }
+}
+
+#[cfg(unix)]
+impl TryFrom<std::os::unix::net::UnixListener> for Listener {
+    type Error = io::Error;
+
+    fn try_from(listener: std::os::unix::net::UnixListener) -> Result<Self, Self::Error> {
+        listener.set_nonblocking(true)?;
+        Ok(tokio::net::UnixListener::from_std(listener)?.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl Accept for Listener {
+    async fn accept(&self) -> io::Result<Connecting> {
+        self.0.accept().await
+    }
+}
+
+pub(crate) struct Listeners(pub(crate) Vec<Listener>);
+
+#[async_trait::async_trait]
+impl Accept for Listeners {
+    async fn accept(&self) -> io::Result<Connecting> {
+        let (accepted, _, _) = select_all(self.0.iter().map(|listener| listener.accept())).await;
+        accepted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use tokio::{
+        io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+        sync::oneshot,
+        task::JoinHandle,
+    };
+
+    use crate::{
+        app::{App, ShutdownReport},
+        http::{get, Router},
+    };
+
+    use super::Listener;
+
+    #[get("/")]
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    fn serve(
+        listeners: Vec<Listener>,
+    ) -> (oneshot::Sender<()>, JoinHandle<io::Result<ShutdownReport>>) {
+        let (signal, receiver) = oneshot::channel::<()>();
+        let task = tokio::spawn(async move {
+            App::from(Router::new().route(index))
+                .serve_with_shutdown(listeners, async {
+                    receiver.await.ok();
+                })
+                .await
+        });
+
+        (signal, task)
+    }
+
+    async fn get<S>(mut stream: S) -> String
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let request = "GET / HTTP/1.1\r\nhost: localhost\r\nconnection: close\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn serves_on_unix_listener() {
+        let path = std::env::temp_dir().join(format!("mincat-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let (signal, task) = serve(vec![Listener::bind_unix(&path).unwrap()]);
+
+        let response = get(tokio::net::UnixStream::connect(&path).await.unwrap()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("index"));
+
+        signal.send(()).unwrap();
+        task.await.unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_on_pre_bound_std_listener() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (signal, task) = serve(vec![Listener::try_from(listener).unwrap()]);
+
+        let response = get(TcpStream::connect(addr).await.unwrap()).await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("index"));
+
+        signal.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_on_several_listeners() {
+        let first = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let second = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addrs = [first.local_addr().unwrap(), second.local_addr().unwrap()];
+        let (signal, task) = serve(vec![first.into(), second.into()]);
+
+        for addr in addrs.into_iter().rev().chain(addrs) {
+            let response = get(TcpStream::connect(addr).await.unwrap()).await;
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+            assert!(response.ends_with("index"));
+        }
+
+        signal.send(()).unwrap();
+        task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn serving_without_listeners_is_an_error() {
+        let error = App::from(Router::new().route(index))
+            .serve(Vec::<Listener>::new())
+            .await
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -6,7 +6,11 @@ pub struct BodyLimit(pub usize);
 
 #[async_trait::async_trait]
 impl Middleware for BodyLimit {
-    async fn call(self: Box<Self>, mut request: Request, next: Next) -> Response {
+    async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
+        self.call_ref(request, next).await
+    }
+
+    async fn call_ref(&self, mut request: Request, next: Next) -> Response {
         request.extensions_mut().insert(BodyLimitedSize(self.0));
         next.run(request).await
     }
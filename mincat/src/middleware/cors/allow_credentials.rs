@@ -65,15 +65,10 @@ impl fmt::Debug for AllowCredentials {
 type PredicateParam =
     Arc<dyn for<'a> Fn(&'a HeaderValue, &'a RequestParts) -> bool + Send + Sync + 'static>;
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 enum AllowCredentialsInner {
     Yes,
+    #[default]
     No,
     Predicate(PredicateParam),
 }
-
-impl Default for AllowCredentialsInner {
-    fn default() -> Self {
-        Self::No
-    }
-}
@@ -26,7 +26,7 @@ impl AllowOrigin {
         I: IntoIterator<Item = HeaderValue>,
     {
         let origins = origins.into_iter().collect::<Vec<_>>();
-        if origins.iter().any(|o| o == WILDCARD) {
+        if origins.contains(&WILDCARD) {
             panic!("Wildcard origin (`*`) cannot be passed to `AllowOrigin::list`. Use `AllowOrigin::any()` instead");
         } else {
             Self(OriginInner::List(origins))
@@ -203,7 +203,11 @@ impl Default for Cors {
 #[async_trait::async_trait]
 impl Middleware for Cors {
     async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
-        ensure_usable_cors_rules(&self);
+        self.call_ref(request, next).await
+    }
+
+    async fn call_ref(&self, request: Request, next: Next) -> Response {
+        ensure_usable_cors_rules(self);
         let (parts, body) = request.into_parts();
         let origin = parts.headers.get(&header::ORIGIN);
         let mut headers = HeaderMap::new();
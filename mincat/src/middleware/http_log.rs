@@ -7,6 +7,10 @@ pub struct HttpLog;
 
 #[async_trait::async_trait]
 impl Middleware for HttpLog {
+    async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
+        self.call_ref(request, next).await
+    }
+
     #[tracing::instrument(
         name = "mincat[http-log]",
         skip(self, request, next),
@@ -15,7 +19,7 @@ impl Middleware for HttpLog {
             method = request.method().to_string()
         )
     )]
-    async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
+    async fn call_ref(&self, request: Request, next: Next) -> Response {
         let start = Instant::now();
         event!(Level::INFO, "REQUEST");
         let res = next.run(request).await;
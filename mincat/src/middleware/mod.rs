@@ -20,3 +20,155 @@ pub use mincat_core::{
 };
 
 pub use mincat_macro::middleware;
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use http::{HeaderValue, StatusCode};
+
+    use crate::{
+        http::{get, Request, Response, Router},
+        test::{TestClient, TestResponse},
+        App,
+    };
+
+    use super::{middleware, Middleware, Next};
+
+    async fn trace(request: Request, next: Next, name: &'static str) -> Response {
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .append("x-trace", HeaderValue::from_static(name));
+        response
+    }
+
+    #[middleware]
+    async fn first(request: Request, next: Next) -> Response {
+        trace(request, next, "first").await
+    }
+
+    #[middleware]
+    async fn second(request: Request, next: Next) -> Response {
+        trace(request, next, "second").await
+    }
+
+    #[middleware]
+    async fn third(request: Request, next: Next) -> Response {
+        trace(request, next, "third").await
+    }
+
+    #[middleware]
+    async fn fourth(request: Request, next: Next) -> Response {
+        trace(request, next, "fourth").await
+    }
+
+    #[get("/hello")]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    fn traced(response: &TestResponse) -> Vec<&str> {
+        response
+            .headers()
+            .get_all("x-trace")
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn later_middleware_wraps_earlier() {
+        let router = Router::new()
+            .route(hello.middleware(first).middleware(second))
+            .middleware(third);
+        let client = TestClient::new(App::from(router).middleware(fourth));
+
+        let response = client.get("/hello").send().await;
+        assert_eq!(traced(&response), ["first", "second", "third", "fourth"]);
+    }
+
+    #[tokio::test]
+    async fn router_middleware_covers_routes_added_later() {
+        let router = Router::new().middleware(first).route(hello);
+        let client = TestClient::new(router);
+
+        let response = client.get("/hello").send().await;
+        assert_eq!(traced(&response), ["first"]);
+    }
+
+    #[tokio::test]
+    async fn grouped_router_middleware_runs_inside_parent_middleware() {
+        let api = Router::new().route(hello).middleware(first);
+        let router = Router::new().group("/api", api).middleware(second);
+        let client = TestClient::new(router);
+
+        let response = client.get("/api/hello").send().await;
+        assert_eq!(traced(&response), ["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn app_middleware_runs_for_unmatched_routes() {
+        let router = Router::new().route(hello).middleware(first);
+        let client = TestClient::new(App::from(router).middleware(second));
+
+        let response = client.get("/missing").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(traced(&response), ["second"]);
+
+        let response = client.post("/hello").send().await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(traced(&response), ["second"]);
+    }
+
+    #[derive(Clone, Default)]
+    struct Counted {
+        clones: Arc<AtomicUsize>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Middleware for Counted {
+        async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
+            self.call_ref(request, next).await
+        }
+
+        async fn call_ref(&self, request: Request, next: Next) -> Response {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            next.run(request).await
+        }
+
+        fn clone_box(&self) -> Box<dyn Middleware> {
+            self.clones.fetch_add(1, Ordering::SeqCst);
+            Box::new(self.clone())
+        }
+    }
+
+    impl From<Counted> for Box<dyn Middleware> {
+        fn from(value: Counted) -> Box<dyn Middleware> {
+            Box::new(value)
+        }
+    }
+
+    #[tokio::test]
+    async fn middleware_is_not_cloned_per_request() {
+        let counted = Counted::default();
+        let router = Router::new()
+            .route(hello.middleware(counted.clone()))
+            .middleware(counted.clone());
+        let client = TestClient::new(App::from(router).middleware(counted.clone()));
+
+        client.get("/hello").send().await;
+        let clones = counted.clones.load(Ordering::SeqCst);
+        for _ in 0..3 {
+            let response = client.get("/hello").send().await;
+            assert_eq!(response.text().await, "hello");
+        }
+
+        assert_eq!(counted.calls.load(Ordering::SeqCst), 12);
+        assert_eq!(counted.clones.load(Ordering::SeqCst), clones);
+    }
+}
@@ -124,13 +124,13 @@ impl StoreSession {
 }
 
 async fn handle(
-    store_session: StoreSession,
+    store_session: &StoreSession,
     request: Request,
     next: Next,
 ) -> Result<Response, Response> {
     store_session.init().await?;
     let (mut parts, body) = request.into_parts();
-    let cookie = PrivateCookieJar::from_request_parts(&mut parts)
+    let cookie = <PrivateCookieJar as FromRequestParts>::from_request_parts(&mut parts)
         .await
         .map_err(|e| e.into_response())?;
     let mut request = Request::from_parts(parts, body);
@@ -158,7 +158,11 @@ async fn handle(
 #[async_trait::async_trait]
 impl Middleware for StoreSession {
     async fn call(self: Box<Self>, request: Request, next: Next) -> Response {
-        handle(*self, request, next).await.into_response()
+        handle(&self, request, next).await.into_response()
+    }
+
+    async fn call_ref(&self, request: Request, next: Next) -> Response {
+        handle(self, request, next).await.into_response()
     }
 
     fn clone_box(&self) -> Box<dyn Middleware> {
@@ -48,7 +48,7 @@ async fn register_key(
     redis::pipe()
         .hset(session_key, "", "")
         .expire(session_key, age)
-        .query_async(&mut conn)
+        .query_async::<_, ()>(&mut conn)
         .await
         .map_err(Error::new)?;
     Ok(())
@@ -62,7 +62,7 @@ async fn set(
 ) -> Result<(), Error> {
     redis::pipe()
         .hset(session_key, key, value)
-        .query_async(&mut conn)
+        .query_async::<_, ()>(&mut conn)
         .await
         .map_err(Error::new)?;
     Ok(())
@@ -0,0 +1,364 @@
+use std::sync::Arc;
+
+use http::{header, HeaderValue, Method};
+use mincat_core::route::{IntoRoute, Route};
+
+pub use mincat_core::openapi::{
+    __private, json, Map, OpenApi, Operation, OperationInput, OperationOutput, ToSchema, Value,
+};
+pub use mincat_macro::ToSchema;
+
+#[cfg(feature = "swagger-ui")]
+mod swagger_ui;
+#[cfg(feature = "swagger-ui")]
+pub use swagger_ui::SwaggerUi;
+
+#[cfg(feature = "path")]
+impl<T> OperationInput for crate::extract::Path<T>
+where
+    T: ToSchema,
+{
+    fn operation(operation: &mut Operation) {
+        operation.path(T::schema());
+    }
+}
+
+#[cfg(feature = "query")]
+impl<T> OperationInput for crate::extract::Query<T>
+where
+    T: ToSchema,
+{
+    fn operation(operation: &mut Operation) {
+        operation.query(T::schema());
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> OperationInput for crate::extract::Json<T>
+where
+    T: ToSchema,
+{
+    fn operation(operation: &mut Operation) {
+        operation.request_body(mime::APPLICATION_JSON.as_ref(), T::schema());
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T> OperationOutput for crate::extract::Json<T>
+where
+    T: ToSchema,
+{
+    fn operation(operation: &mut Operation) {
+        operation.response(mime::APPLICATION_JSON.as_ref(), T::schema());
+    }
+}
+
+#[cfg(feature = "form")]
+impl<T> OperationInput for crate::extract::form::FormUrlencoded<T>
+where
+    T: ToSchema,
+{
+    fn operation(operation: &mut Operation) {
+        operation.request_body(mime::APPLICATION_WWW_FORM_URLENCODED.as_ref(), T::schema());
+    }
+}
+
+#[cfg(feature = "form")]
+impl<T> OperationInput for crate::extract::form::FormData<T>
+where
+    T: ToSchema + crate::extract::form::FromMultipart + crate::extract::form::FromMultipartNull,
+{
+    fn operation(operation: &mut Operation) {
+        operation.request_body(mime::MULTIPART_FORM_DATA.as_ref(), T::schema());
+    }
+}
+
+#[cfg(feature = "form")]
+impl ToSchema for crate::extract::form_data::Null {
+    fn schema() -> Value {
+        json!({ "type": "object" })
+    }
+}
+
+#[derive(Clone)]
+pub struct OpenApiRoute {
+    path: String,
+    document: Arc<String>,
+}
+
+impl OpenApiRoute {
+    pub fn new<T>(path: T, openapi: &OpenApi) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            path: path.into(),
+            document: Arc::new(openapi.to_string()),
+        }
+    }
+}
+
+impl From<OpenApiRoute> for Route {
+    fn from(route: OpenApiRoute) -> Route {
+        let document = route.document;
+        Route::init(Method::GET, route.path, move || {
+            let document = document.clone();
+            async move {
+                (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(mime::APPLICATION_JSON.as_ref()),
+                    )],
+                    document.as_str().to_string(),
+                )
+            }
+        })
+    }
+}
+
+impl<S> IntoRoute<S> for OpenApiRoute {
+    fn into_routes(self) -> Vec<Route> {
+        vec![self.into()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::{
+        extract::{form::FormUrlencoded, Json, Path, Query},
+        http::{any, get, post, put, Router},
+    };
+
+    use super::{json, ToSchema};
+
+    #[derive(Clone, Deserialize, ToSchema)]
+    struct Pagination {
+        page: u32,
+        per_page: Option<u32>,
+    }
+
+    #[derive(Clone, Deserialize, ToSchema)]
+    struct User {
+        name: String,
+    }
+
+    /// Get a file
+    ///
+    /// Reads a file of a user.
+    #[get("/users/:id/files/*rest", name = "user_file")]
+    async fn user_file(Path((id, rest)): Path<(u64, String)>) -> String {
+        format!("{id} {rest}")
+    }
+
+    #[get("/users")]
+    async fn list_users(Query(pagination): Query<Pagination>) -> Json<Vec<String>> {
+        let _ = (pagination.page, pagination.per_page);
+        Json(vec![])
+    }
+
+    #[post("/users")]
+    async fn create_user(Json(user): Json<User>) -> String {
+        user.name
+    }
+
+    #[put("/users/:id")]
+    async fn update_user(FormUrlencoded(user): FormUrlencoded<User>) -> String {
+        user.name
+    }
+
+    #[any("/users/:id")]
+    async fn any_user() -> &'static str {
+        "any"
+    }
+
+    #[test]
+    fn documents_operations() {
+        let router = Router::new()
+            .route(user_file)
+            .route(list_users)
+            .route(create_user)
+            .route(update_user)
+            .route(any_user);
+        let document = router.openapi().title("users").version("1.0.0").to_value();
+
+        assert_eq!(
+            document["info"],
+            json!({ "title": "users", "version": "1.0.0" })
+        );
+
+        let paths = &document["paths"];
+        assert_eq!(
+            paths["/users/{id}/files/{rest}"]["get"],
+            json!({
+                "summary": "Get a file",
+                "description": "Reads a file of a user.",
+                "operationId": "user_file",
+                "parameters": [
+                    {
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "integer", "format": "int64" },
+                    },
+                    {
+                        "name": "rest",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "string" },
+                    },
+                ],
+                "responses": { "200": { "description": "OK" } },
+            })
+        );
+
+        assert_eq!(
+            paths["/users"]["get"]["parameters"],
+            json!([
+                {
+                    "name": "page",
+                    "in": "query",
+                    "required": true,
+                    "schema": { "type": "integer", "format": "int64" },
+                },
+                {
+                    "name": "per_page",
+                    "in": "query",
+                    "required": false,
+                    "schema": { "type": "integer", "format": "int64" },
+                },
+            ])
+        );
+        assert_eq!(
+            paths["/users"]["get"]["responses"]["200"]["content"],
+            json!({
+                "application/json": {
+                    "schema": { "type": "array", "items": { "type": "string" } },
+                },
+            })
+        );
+
+        let user = User::schema();
+        assert_eq!(
+            paths["/users"]["post"]["requestBody"],
+            json!({
+                "required": true,
+                "content": { "application/json": { "schema": user } },
+            })
+        );
+        assert_eq!(
+            paths["/users/{id}"]["put"]["requestBody"]["content"],
+            json!({ "application/x-www-form-urlencoded": { "schema": user } })
+        );
+    }
+
+    #[test]
+    fn any_route_fills_the_remaining_methods() {
+        let router = Router::new().route(update_user).route(any_user);
+        let document = router.openapi().to_value();
+
+        let methods = document["paths"]["/users/{id}"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            methods,
+            ["delete", "get", "head", "options", "patch", "post", "put", "trace"]
+        );
+        assert!(document["paths"]["/users/{id}"]["put"]["requestBody"].is_object());
+        assert!(document["paths"]["/users/{id}"]["get"]
+            .get("requestBody")
+            .is_none());
+    }
+
+    #[test]
+    fn host_routers_are_documented_separately() {
+        let router = Router::new()
+            .route(list_users)
+            .host("api.localhost", Router::new().route(create_user))
+            .host(":tenant.localhost", Router::new().route(update_user));
+
+        let document = router.openapi().to_value();
+        assert_eq!(
+            document["paths"],
+            json!({ "/users": document["paths"]["/users"] })
+        );
+        assert!(document["paths"]["/users"].get("post").is_none());
+
+        let document = router.openapi().host("api.localhost").to_value();
+        assert_eq!(
+            document["paths"]["/users"]
+                .as_object()
+                .unwrap()
+                .keys()
+                .collect::<Vec<_>>(),
+            ["post"]
+        );
+
+        let document = router.openapi().host(":tenant.localhost").to_value();
+        assert!(document["paths"]["/users/{id}"]["put"].is_object());
+        assert!(document["paths"].get("/users").is_none());
+    }
+
+    #[derive(Clone, Deserialize, ToSchema)]
+    #[serde(rename_all = "kebab-case")]
+    enum Theme {
+        DarkMode,
+        Light,
+    }
+
+    #[derive(Clone, Deserialize, ToSchema)]
+    #[serde(rename_all = "camelCase")]
+    struct Settings {
+        user_name: String,
+        #[serde(default)]
+        page_size: u32,
+        #[serde(rename = "theme")]
+        color_theme: Theme,
+    }
+
+    #[derive(Clone, Default, Deserialize, ToSchema)]
+    #[serde(default)]
+    struct Filter {
+        tag: String,
+    }
+
+    #[post("/settings")]
+    async fn save_settings(Query(filter): Query<Filter>, Json(settings): Json<Settings>) -> String {
+        let theme = matches!(settings.color_theme, Theme::Light);
+        format!(
+            "{} {} {} {theme}",
+            filter.tag, settings.user_name, settings.page_size
+        )
+    }
+
+    #[test]
+    fn schema_follows_serde_attributes() {
+        let document = Router::new().route(save_settings).openapi().to_value();
+        let operation = &document["paths"]["/settings"]["post"];
+        assert_eq!(
+            operation["parameters"],
+            json!([{
+                "name": "tag",
+                "in": "query",
+                "required": false,
+                "schema": { "type": "string" },
+            }])
+        );
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "userName": { "type": "string" },
+                    "pageSize": { "type": "integer", "format": "int64" },
+                    "theme": { "type": "string", "enum": ["dark-mode", "light"] },
+                },
+                "required": ["userName", "theme"],
+            })
+        );
+    }
+}
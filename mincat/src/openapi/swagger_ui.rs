@@ -0,0 +1,117 @@
+use http::{header, HeaderValue, Method};
+use mincat_core::route::{IntoRoute, Route};
+
+const INDEX_HTML: &str = include_str!("swagger_ui.html");
+
+const ASSETS_URL: &str = "https://cdn.jsdelivr.net/npm/swagger-ui-dist@5.17.14";
+
+#[derive(Clone)]
+pub struct SwaggerUi {
+    path: String,
+    url: String,
+    assets_url: String,
+}
+
+impl SwaggerUi {
+    pub fn new<P, U>(path: P, url: U) -> Self
+    where
+        P: Into<String>,
+        U: Into<String>,
+    {
+        Self {
+            path: path.into(),
+            url: url.into(),
+            assets_url: ASSETS_URL.to_string(),
+        }
+    }
+
+    /// Loads `swagger-ui.css` and `swagger-ui-bundle.js` from `url` instead of
+    /// the pinned jsDelivr release, e.g. a mirror or a [`StaticDir`] serving a
+    /// copy of `swagger-ui-dist`.
+    ///
+    /// [`StaticDir`]: crate::route::StaticDir
+    pub fn assets_url<A>(mut self, url: A) -> Self
+    where
+        A: Into<String>,
+    {
+        self.assets_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn index_html(&self) -> String {
+        let url = serde_json::to_string(&self.url)
+            .expect("string serialize failed")
+            .replace('<', "\\u003c");
+        let assets = self
+            .assets_url
+            .replace('&', "&amp;")
+            .replace('"', "&quot;")
+            .replace('<', "&lt;");
+        INDEX_HTML
+            .replace("{{assets}}", &assets)
+            .replace("{{url}}", &url)
+    }
+}
+
+impl From<SwaggerUi> for Route {
+    fn from(swagger_ui: SwaggerUi) -> Route {
+        let index_html = swagger_ui.index_html();
+        Route::init(Method::GET, swagger_ui.path, move || {
+            let index_html = index_html.clone();
+            async move {
+                (
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static(mime::TEXT_HTML_UTF_8.as_ref()),
+                    )],
+                    index_html,
+                )
+            }
+        })
+    }
+}
+
+impl<S> IntoRoute<S> for SwaggerUi {
+    fn into_routes(self) -> Vec<Route> {
+        vec![self.into()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{http::Router, test::TestClient};
+
+    use super::SwaggerUi;
+
+    #[tokio::test]
+    async fn loads_assets_from_the_pinned_cdn() {
+        let router = Router::new().route(SwaggerUi::new("/docs", "/openapi.json"));
+        let client = TestClient::new(router);
+
+        let response = client.get("/docs").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let html = response.text().await;
+        assert!(html.contains(
+            r#"href="https://cdn.jsdelivr.net/npm/swagger-ui-dist@5.17.14/swagger-ui.css""#
+        ));
+        assert!(html.contains(
+            r#"src="https://cdn.jsdelivr.net/npm/swagger-ui-dist@5.17.14/swagger-ui-bundle.js""#
+        ));
+        assert!(html.contains(r#"url: "/openapi.json""#));
+
+        let response = client.get("/docs/swagger-ui.css").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn assets_url_can_point_at_a_mirror() {
+        let swagger_ui = SwaggerUi::new("/docs", "/openapi.json").assets_url("/static/swagger/");
+        let client = TestClient::new(Router::new().route(swagger_ui));
+
+        let html = client.get("/docs").send().await.text().await;
+        assert!(html.contains(r#"href="/static/swagger/swagger-ui.css""#));
+        assert!(html.contains(r#"src="/static/swagger/swagger-ui-bundle.js""#));
+    }
+}
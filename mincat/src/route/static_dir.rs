@@ -6,12 +6,12 @@ use mincat_core::{
     middleware::Middleware,
     request::{FromRequestParts, Parts},
     response::{IntoResponse, Response},
-    route::Route,
+    route::{IntoRoute, Route},
 };
 use tokio::fs::File;
 use tokio_util::io::ReaderStream;
 
-use crate::app::MincatRoutePath;
+use crate::extract::RawPathParams;
 
 #[derive(Clone, Builder)]
 pub struct StaticDir {
@@ -37,6 +37,12 @@ impl StaticDir {
     }
 
     async fn open_file(&self) -> Result<(File, String), Error> {
+        if self.file_path.split(['/', '\\']).any(|item| item == "..") {
+            return Err(Error::new(std::io::Error::from(
+                std::io::ErrorKind::NotFound,
+            )));
+        }
+
         let file_path = self.get_file_path();
         let file = match File::open(&file_path).await {
             Ok(file) => (file, file_path),
@@ -103,25 +109,28 @@ impl From<StaticDir> for Route {
     }
 }
 
+impl<S> IntoRoute<S> for StaticDir {
+    fn into_routes(self) -> Vec<Route> {
+        vec![self.into()]
+    }
+}
+
 struct FilePath(String);
 
 #[async_trait::async_trait]
-impl FromRequestParts for FilePath {
+impl<S> FromRequestParts<S> for FilePath {
     type Error = Error;
 
     async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
-        let MincatRoutePath(define_path) = parts
+        let value = parts
             .extensions
-            .get::<MincatRoutePath>()
-            .ok_or(Error::new("missing path"))?;
-
-        let real_path = parts.uri.path();
-        let mut router = matchit::Router::new();
-        router.insert(define_path, true).map_err(Error::new)?;
-        let matched = router.at(real_path).map_err(Error::new)?;
-        let value = matched.params.get("file").unwrap_or("");
+            .get::<RawPathParams>()
+            .ok_or(Error::new("missing path"))?
+            .decoded("file")
+            .map_err(Error::new)?
+            .unwrap_or_default();
 
-        Ok(FilePath(value.to_string()))
+        Ok(FilePath(value))
     }
 }
 
@@ -142,3 +151,48 @@ async fn static_dir_handle(static_dir: StaticDir) -> Result<Response, Error> {
     )
         .into_response())
 }
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::{http::Router, test::TestClient};
+
+    use super::StaticDirBuilder;
+
+    #[tokio::test]
+    async fn encoded_parent_segments_are_not_served() {
+        let root = std::env::temp_dir().join(format!("mincat-static-{}", std::process::id()));
+        let public = root.join("public");
+        std::fs::create_dir_all(&public).unwrap();
+        std::fs::write(public.join("index.txt"), "index").unwrap();
+        std::fs::write(public.join("404.txt"), "missing").unwrap();
+        std::fs::write(root.join("secret.txt"), "secret").unwrap();
+
+        let static_dir = StaticDirBuilder::default()
+            .route_path("/static")
+            .static_dir_path(public.to_str().unwrap())
+            .not_found_file_path(public.join("404.txt").to_str().unwrap())
+            .build()
+            .unwrap();
+        let client = TestClient::new(Router::new().route(static_dir));
+
+        let response = client.get("/static/index.txt").send().await;
+        assert_eq!(response.text().await, "index");
+
+        let response = client.get("/static/secret.txt").send().await;
+        assert_eq!(response.text().await, "missing");
+
+        for uri in [
+            "/static/%2e%2e/secret.txt",
+            "/static/%2E%2E/secret.txt",
+            "/static/..%2fsecret.txt",
+            "/static/%2e%2e%5csecret.txt",
+        ] {
+            let response = client.get(uri).send().await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+        }
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
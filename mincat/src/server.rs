@@ -0,0 +1,243 @@
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::Bytes;
+use http::HeaderMap;
+use http_body::{Frame, SizeHint};
+use hyper_util::{
+    rt::{TokioExecutor, TokioTimer},
+    server::conn::auto::Builder,
+};
+use mincat_core::{body::Body, error::Error};
+use pin_project_lite::pin_project;
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    time::Instant,
+};
+
+const MIN_READ_BUF_SIZE: usize = 8192;
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default)]
+pub struct ServerConfig {
+    header_read_timeout: Option<Duration>,
+    keep_alive_timeout: Option<Duration>,
+    http2_max_concurrent_streams: Option<u32>,
+    max_connections: Option<usize>,
+    max_header_size: Option<usize>,
+    tls_handshake_timeout: Option<Duration>,
+}
+
+impl ServerConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn header_read_timeout(mut self, timeout: Duration) -> Self {
+        self.header_read_timeout = Some(timeout);
+        self
+    }
+
+    pub fn keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.keep_alive_timeout = Some(timeout);
+        self
+    }
+
+    pub fn http2_max_concurrent_streams(mut self, max: u32) -> Self {
+        self.http2_max_concurrent_streams = Some(max);
+        self
+    }
+
+    pub fn max_connections(mut self, max: usize) -> Self {
+        assert!(max > 0, "max_connections must be greater than zero");
+        self.max_connections = Some(max);
+        self
+    }
+
+    /// Limits the total size of request header names and values.
+    ///
+    /// Requests over the limit are answered with 431. The check runs on the
+    /// parsed header map, after hyper has read the request head; for HTTP/1
+    /// hyper's read buffer is capped at the same limit. Hyper cannot read with
+    /// a buffer under 8 KiB, so smaller limits panic.
+    pub fn max_header_size(mut self, max: usize) -> Self {
+        assert!(
+            max >= MIN_READ_BUF_SIZE,
+            "max_header_size must be at least {MIN_READ_BUF_SIZE} bytes"
+        );
+        self.max_header_size = Some(max);
+        self
+    }
+
+    pub fn tls_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.tls_handshake_timeout = Some(timeout);
+        self
+    }
+
+    pub(crate) fn builder(&self) -> Builder<TokioExecutor> {
+        let mut builder = Builder::new(TokioExecutor::new());
+        builder.http1().timer(TokioTimer::new());
+        builder.http2().timer(TokioTimer::new());
+
+        if let Some(timeout) = self.header_read_timeout {
+            builder.http1().header_read_timeout(timeout);
+        }
+
+        if let Some(max) = self.http2_max_concurrent_streams {
+            builder.http2().max_concurrent_streams(max);
+        }
+
+        if let Some(max) = self.max_header_size {
+            builder.http1().max_buf_size(max);
+            builder
+                .http2()
+                .max_header_list_size(max.try_into().unwrap_or(u32::MAX));
+        }
+
+        builder
+    }
+
+    pub(crate) fn connection_limit(&self) -> Option<Arc<Semaphore>> {
+        self.max_connections
+            .map(|max| Arc::new(Semaphore::new(max)))
+    }
+
+    pub(crate) fn header_too_large(&self, headers: &HeaderMap) -> bool {
+        let Some(max) = self.max_header_size else {
+            return false;
+        };
+
+        let size = headers
+            .iter()
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum::<usize>();
+
+        size > max
+    }
+
+    pub(crate) fn keep_alive(&self) -> Option<Duration> {
+        self.keep_alive_timeout
+    }
+
+    pub(crate) fn handshake_timeout(&self) -> Duration {
+        self.tls_handshake_timeout.unwrap_or(TLS_HANDSHAKE_TIMEOUT)
+    }
+}
+
+pub(crate) async fn acquire(limit: &Option<Arc<Semaphore>>) -> Option<OwnedSemaphorePermit> {
+    match limit {
+        Some(limit) => limit.clone().acquire_owned().await.ok(),
+        None => None,
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Idle(Arc<IdleState>);
+
+struct IdleState {
+    in_flight: AtomicUsize,
+    last_active: Mutex<Instant>,
+}
+
+pub(crate) struct IdleGuard(Idle);
+
+impl Idle {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(IdleState {
+            in_flight: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+        }))
+    }
+
+    pub(crate) fn busy(&self) -> IdleGuard {
+        self.0.in_flight.fetch_add(1, Ordering::SeqCst);
+        self.touch();
+        IdleGuard(self.clone())
+    }
+
+    fn done(&self) {
+        self.touch();
+        self.0.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn touch(&self) {
+        *self.0.last_active.lock().unwrap() = Instant::now();
+    }
+
+    pub(crate) async fn timeout(&self, timeout: Option<Duration>) {
+        let Some(timeout) = timeout else {
+            return std::future::pending().await;
+        };
+
+        loop {
+            let deadline = *self.0.last_active.lock().unwrap() + timeout;
+            if self.0.in_flight.load(Ordering::SeqCst) > 0 {
+                tokio::time::sleep(timeout).await;
+                continue;
+            }
+
+            if deadline <= Instant::now() {
+                return;
+            }
+
+            tokio::time::sleep_until(deadline).await;
+        }
+    }
+}
+
+impl Drop for IdleGuard {
+    fn drop(&mut self) {
+        self.0.done();
+    }
+}
+
+pin_project! {
+    // Keeps the connection busy until the response body has been fully sent or dropped.
+    pub(crate) struct IdleBody {
+        #[pin]
+        body: Body,
+        guard: Option<IdleGuard>,
+    }
+}
+
+impl IdleBody {
+    pub(crate) fn new(body: Body, guard: IdleGuard) -> Self {
+        Self {
+            body,
+            guard: Some(guard),
+        }
+    }
+}
+
+impl http_body::Body for IdleBody {
+    type Data = Bytes;
+    type Error = Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.project();
+        let frame = this.body.poll_frame(cx);
+        if matches!(frame, Poll::Ready(None | Some(Err(_)))) {
+            this.guard.take();
+        }
+
+        frame
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
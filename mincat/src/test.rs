@@ -0,0 +1,371 @@
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use bytes::Bytes;
+use http::{
+    header::{self, HeaderName},
+    request::Builder,
+    HeaderMap, HeaderValue, Method, StatusCode,
+};
+use http_body_util::BodyExt;
+use mincat_core::{body::Body, request::Request, response::Response};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{app::App, extract::ConnectInfo};
+
+#[derive(Clone)]
+pub struct TestClient {
+    app: Arc<App>,
+    cookies: Arc<Mutex<BTreeMap<String, String>>>,
+}
+
+impl TestClient {
+    pub fn new<T, S>(app: T) -> Self
+    where
+        T: Into<App<S>>,
+    {
+        Self {
+            app: Arc::new(app.into().into_untyped()),
+            cookies: Default::default(),
+        }
+    }
+
+    pub fn request(&self, method: Method, uri: &str) -> TestRequest {
+        TestRequest {
+            client: self.clone(),
+            builder: Request::builder().method(method).uri(uri),
+            body: Body::empty(),
+        }
+    }
+
+    pub fn get(&self, uri: &str) -> TestRequest {
+        self.request(Method::GET, uri)
+    }
+
+    pub fn post(&self, uri: &str) -> TestRequest {
+        self.request(Method::POST, uri)
+    }
+
+    pub fn put(&self, uri: &str) -> TestRequest {
+        self.request(Method::PUT, uri)
+    }
+
+    pub fn patch(&self, uri: &str) -> TestRequest {
+        self.request(Method::PATCH, uri)
+    }
+
+    pub fn delete(&self, uri: &str) -> TestRequest {
+        self.request(Method::DELETE, uri)
+    }
+
+    pub fn head(&self, uri: &str) -> TestRequest {
+        self.request(Method::HEAD, uri)
+    }
+
+    pub fn options(&self, uri: &str) -> TestRequest {
+        self.request(Method::OPTIONS, uri)
+    }
+
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.cookies.lock().unwrap().get(name).cloned()
+    }
+
+    fn cookie_header(&self) -> Option<HeaderValue> {
+        let cookies = self.cookies.lock().unwrap();
+        if cookies.is_empty() {
+            return None;
+        }
+
+        let value = cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        HeaderValue::from_str(&value).ok()
+    }
+
+    fn store_cookies(&self, headers: &HeaderMap) {
+        let mut cookies = self.cookies.lock().unwrap();
+        for value in headers.get_all(header::SET_COOKIE) {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+
+            let mut attrs = value.split(';');
+            let Some((name, value)) = attrs.next().and_then(|pair| pair.split_once('=')) else {
+                continue;
+            };
+
+            let expired = attrs.any(|attr| {
+                attr.trim()
+                    .split_once('=')
+                    .is_some_and(|(key, value)| key.eq_ignore_ascii_case("max-age") && value == "0")
+            });
+
+            let (name, value) = (name.trim(), value.trim());
+            if expired || value.is_empty() {
+                cookies.remove(name);
+            } else {
+                cookies.insert(name.to_string(), value.to_string());
+            }
+        }
+    }
+}
+
+pub struct TestRequest {
+    client: TestClient,
+    builder: Builder,
+    body: Body,
+}
+
+impl TestRequest {
+    pub fn header<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: TryInto<HeaderName>,
+        K::Error: Into<http::Error>,
+        V: TryInto<HeaderValue>,
+        V::Error: Into<http::Error>,
+    {
+        self.builder = self.builder.header(key, value);
+        self
+    }
+
+    pub fn peer_addr(mut self, addr: SocketAddr) -> Self {
+        self.builder = self.builder.extension(ConnectInfo {
+            peer_addr: Some(addr),
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn body<T>(mut self, body: T) -> Self
+    where
+        T: Into<Body>,
+    {
+        self.body = body.into();
+        self
+    }
+
+    pub fn json<T>(self, value: &T) -> Self
+    where
+        T: Serialize,
+    {
+        let body = serde_json::to_vec(value).expect("json serialize failed");
+        self.header(header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref())
+            .body(Bytes::from(body))
+    }
+
+    pub fn form<T>(self, value: &T) -> Self
+    where
+        T: Serialize,
+    {
+        let body = serde_urlencoded::to_string(value).expect("form serialize failed");
+        self.header(
+            header::CONTENT_TYPE,
+            mime::APPLICATION_WWW_FORM_URLENCODED.as_ref(),
+        )
+        .body(body)
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let TestRequest {
+            client,
+            mut builder,
+            body,
+        } = self;
+
+        if let Some(cookie) = client.cookie_header() {
+            builder = builder.header(header::COOKIE, cookie);
+        }
+
+        let request = builder.body(body).expect("test request build failed");
+        let response = client.app.dispatch(request).await;
+        client.store_cookies(response.headers());
+
+        TestResponse(response)
+    }
+}
+
+pub struct TestResponse(Response);
+
+impl TestResponse {
+    pub fn status(&self) -> StatusCode {
+        self.0.status()
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        self.0.headers()
+    }
+
+    pub fn into_inner(self) -> Response {
+        self.0
+    }
+
+    pub async fn bytes(self) -> Bytes {
+        self.0
+            .into_body()
+            .collect()
+            .await
+            .expect("response body read failed")
+            .to_bytes()
+    }
+
+    pub async fn text(self) -> String {
+        String::from_utf8(self.bytes().await.to_vec()).expect("response body is not utf-8")
+    }
+
+    pub async fn json<T>(self) -> T
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(&self.bytes().await).expect("response body is not valid json")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use http::{header, StatusCode};
+    use mincat_core::request::{FromRequestParts, Parts};
+
+    use crate::{
+        http::{get, Router},
+        route::StaticDirBuilder,
+    };
+
+    use super::TestClient;
+
+    struct Cookie(String);
+
+    #[async_trait::async_trait]
+    impl<S> FromRequestParts<S> for Cookie {
+        type Error = Infallible;
+
+        async fn from_request_parts(parts: &mut Parts) -> Result<Self, Self::Error> {
+            let cookie = parts
+                .headers
+                .get(header::COOKIE)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("anonymous");
+
+            Ok(Cookie(cookie.to_string()))
+        }
+    }
+
+    #[get("/login")]
+    async fn login() -> [(header::HeaderName, &'static str); 1] {
+        [(header::SET_COOKIE, "session=abc; Path=/")]
+    }
+
+    #[get("/logout")]
+    async fn logout() -> [(header::HeaderName, &'static str); 1] {
+        [(header::SET_COOKIE, "session=; Max-Age=0")]
+    }
+
+    #[get("/whoami")]
+    async fn whoami(Cookie(cookie): Cookie) -> String {
+        cookie
+    }
+
+    fn client() -> TestClient {
+        TestClient::new(Router::new().route(login).route(logout).route(whoami))
+    }
+
+    #[tokio::test]
+    async fn keeps_cookies_between_requests() {
+        let client = client();
+        assert_eq!(client.get("/whoami").send().await.text().await, "anonymous");
+
+        client.get("/login").send().await;
+        assert_eq!(client.cookie("session").as_deref(), Some("abc"));
+        assert_eq!(
+            client.get("/whoami").send().await.text().await,
+            "session=abc"
+        );
+
+        client.get("/logout").send().await;
+        assert_eq!(client.cookie("session"), None);
+        assert_eq!(client.get("/whoami").send().await.text().await, "anonymous");
+    }
+
+    #[tokio::test]
+    async fn unknown_path_is_not_found() {
+        let response = client().get("/missing").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn serves_static_files_under_a_nested_prefix() {
+        let root = std::env::temp_dir().join(format!("mincat-test-client-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("css")).unwrap();
+        std::fs::write(root.join("css/app.css"), "body {}").unwrap();
+        std::fs::write(root.join("404.txt"), "missing").unwrap();
+
+        let static_dir = StaticDirBuilder::default()
+            .route_path("/static")
+            .static_dir_path(root.to_str().unwrap())
+            .not_found_file_path(root.join("404.txt").to_str().unwrap())
+            .build()
+            .unwrap();
+        let client =
+            TestClient::new(Router::new().nest("/assets", Router::new().route(static_dir)));
+
+        let response = client.get("/assets/static/css/app.css").send().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/css"
+        );
+        assert_eq!(response.text().await, "body {}");
+
+        let response = client
+            .get("/assets/static/assets/static/css/app.css")
+            .send()
+            .await;
+        assert_eq!(response.text().await, "missing");
+
+        let response = client.get("/static/css/app.css").send().await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(feature = "json")]
+    mod json {
+        use http::StatusCode;
+        use serde::{Deserialize, Serialize};
+
+        use crate::{
+            extract::Json,
+            http::{post, Router},
+            test::TestClient,
+        };
+
+        #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+        struct User {
+            name: String,
+        }
+
+        #[post("/users")]
+        async fn create_user(Json(user): Json<User>) -> Json<User> {
+            Json(user)
+        }
+
+        #[tokio::test]
+        async fn sends_and_reads_json() {
+            let user = User {
+                name: "mincat".to_string(),
+            };
+
+            let client = TestClient::new(Router::new().route(create_user));
+            let response = client.post("/users").json(&user).send().await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.json::<User>().await, user);
+        }
+    }
+}
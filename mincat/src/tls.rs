@@ -0,0 +1,503 @@
+use std::{
+    fs::File,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    sync::{Arc, OnceLock, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use tokio::{net::TcpListener, task::JoinHandle};
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        server::{ClientHello, ResolvesServerCert},
+        sign::CertifiedKey,
+        ServerConfig,
+    },
+    TlsAcceptor,
+};
+
+use crate::{
+    extract::{ConnectInfo, TlsInfo},
+    listener::{Accept, Connecting, Io},
+};
+
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    resolver: Arc<CertResolver>,
+    reload_interval: Option<Duration>,
+}
+
+impl TlsConfig {
+    pub fn from_pem_file<C, K>(cert_path: C, key_path: K) -> io::Result<Self>
+    where
+        C: AsRef<Path>,
+        K: AsRef<Path>,
+    {
+        let resolver = CertResolver::new(cert_path.as_ref(), key_path.as_ref())?;
+
+        Ok(Self {
+            resolver: Arc::new(resolver),
+            reload_interval: None,
+        })
+    }
+
+    pub fn reload_interval(mut self, interval: Duration) -> Self {
+        self.reload_interval = Some(interval);
+        self
+    }
+
+    pub fn reload(&self) -> io::Result<()> {
+        self.resolver.reload()
+    }
+
+    fn server_config(&self) -> io::Result<ServerConfig> {
+        let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(invalid_data)?
+            .with_no_client_auth()
+            .with_cert_resolver(self.resolver.clone());
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+        Ok(config)
+    }
+
+    fn watch(&self) -> Option<JoinHandle<()>> {
+        let interval = self.reload_interval?;
+        let resolver = self.resolver.clone();
+
+        Some(tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if !resolver.is_modified() {
+                    continue;
+                }
+
+                match resolver.reload() {
+                    Ok(_) => tracing::info!("tls certificate reloaded"),
+                    Err(e) => tracing::error!("tls certificate reload error: {e}"),
+                }
+            }
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct CertResolver {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    key: RwLock<Arc<CertifiedKey>>,
+    modified: RwLock<Option<SystemTime>>,
+}
+
+impl CertResolver {
+    fn new(cert_path: &Path, key_path: &Path) -> io::Result<Self> {
+        let key = load_certified_key(cert_path, key_path)?;
+
+        Ok(Self {
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            key: RwLock::new(Arc::new(key)),
+            modified: RwLock::new(modified(cert_path, key_path)),
+        })
+    }
+
+    fn is_modified(&self) -> bool {
+        modified(&self.cert_path, &self.key_path) != *self.modified.read().unwrap()
+    }
+
+    fn reload(&self) -> io::Result<()> {
+        let modified = modified(&self.cert_path, &self.key_path);
+        let key = load_certified_key(&self.cert_path, &self.key_path)?;
+        *self.key.write().unwrap() = Arc::new(key);
+        *self.modified.write().unwrap() = modified;
+
+        Ok(())
+    }
+}
+
+impl ResolvesServerCert for CertResolver {
+    fn resolve(&self, _: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.key.read().unwrap().clone())
+    }
+}
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn modified(cert_path: &Path, key_path: &Path) -> Option<SystemTime> {
+    let cert = std::fs::metadata(cert_path)
+        .and_then(|m| m.modified())
+        .ok()?;
+    let key = std::fs::metadata(key_path)
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(cert.max(key))
+}
+
+fn load_certified_key(cert_path: &Path, key_path: &Path) -> io::Result<CertifiedKey> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(invalid_data(format!(
+            "no certificate found in {}",
+            cert_path.display()
+        )));
+    }
+
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key_path)?))?
+        .ok_or_else(|| invalid_data(format!("no private key found in {}", key_path.display())))?;
+    let key = ring::sign::any_supported_type(&key).map_err(invalid_data)?;
+
+    Ok(CertifiedKey::new(certs, key))
+}
+
+pub(crate) struct TlsListener {
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    config: TlsConfig,
+    // Started on the first accept, so the listener can be built outside a runtime.
+    watcher: OnceLock<Option<JoinHandle<()>>>,
+}
+
+impl TlsListener {
+    pub(crate) fn new(listener: TcpListener, config: &TlsConfig) -> io::Result<Self> {
+        let acceptor = TlsAcceptor::from(Arc::new(config.server_config()?));
+
+        Ok(Self {
+            listener,
+            acceptor,
+            config: config.clone(),
+            watcher: OnceLock::new(),
+        })
+    }
+}
+
+impl Drop for TlsListener {
+    fn drop(&mut self) {
+        if let Some(Some(watcher)) = self.watcher.take() {
+            watcher.abort();
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Accept for TlsListener {
+    async fn accept(&self) -> io::Result<Connecting> {
+        self.watcher.get_or_init(|| self.config.watch());
+        let (stream, peer_addr) = self.listener.accept().await?;
+        let local_addr = stream.local_addr().ok();
+        let acceptor = self.acceptor.clone();
+
+        Ok(Box::pin(async move {
+            let stream = acceptor.accept(stream).await?;
+            let (_, conn) = stream.get_ref();
+            let tls = TlsInfo {
+                server_name: conn.server_name().map(str::to_string),
+                alpn_protocol: conn
+                    .alpn_protocol()
+                    .map(|protocol| String::from_utf8_lossy(protocol).into_owned()),
+                protocol_version: conn
+                    .protocol_version()
+                    .map(|version| format!("{version:?}")),
+            };
+            let info = ConnectInfo {
+                peer_addr: Some(peer_addr),
+                local_addr,
+                tls: Some(tls),
+            };
+
+            Ok((Box::new(stream) as Box<dyn Io>, info))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::{self, File},
+        path::{Path, PathBuf},
+        time::{Duration, SystemTime},
+    };
+
+    use std::sync::Arc;
+
+    use http::Request;
+    use http_body_util::{BodyExt, Empty};
+    use hyper::body::Bytes;
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use tokio::{
+        io::AsyncReadExt,
+        net::{TcpListener, TcpStream},
+        sync::oneshot,
+    };
+    use tokio_rustls::{
+        rustls::{
+            client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+            crypto::{self, ring, WebPkiSupportedAlgorithms},
+            pki_types::{CertificateDer, ServerName, UnixTime},
+            ClientConfig, DigitallySignedStruct, Error, SignatureScheme,
+        },
+        TlsConnector,
+    };
+
+    use crate::{
+        extract::ConnectInfo,
+        http::{get, Router},
+        App, Listener, ServerConfig,
+    };
+
+    use super::TlsConfig;
+
+    const CERTS: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../examples/tls/self_signed_certs"
+    );
+    const RENEWED_CERTS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/certs");
+
+    fn config() -> TlsConfig {
+        TlsConfig::from_pem_file(format!("{CERTS}/cert.pem"), format!("{CERTS}/key.pem")).unwrap()
+    }
+
+    fn cert_der(dir: &str) -> Vec<u8> {
+        let pem = fs::read(format!("{dir}/cert.pem")).unwrap();
+        let cert = rustls_pemfile::certs(&mut pem.as_slice())
+            .next()
+            .unwrap()
+            .unwrap();
+        cert.to_vec()
+    }
+
+    fn served_cert(config: &TlsConfig) -> Vec<u8> {
+        config.resolver.key.read().unwrap().cert[0].to_vec()
+    }
+
+    fn write_pem(dir: &Path, name: &str, contents: &[u8], modified: SystemTime) {
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    fn install(dir: &Path, certs: &str, modified: SystemTime) {
+        for name in ["cert.pem", "key.pem"] {
+            let contents = fs::read(format!("{certs}/{name}")).unwrap();
+            write_pem(dir, name, &contents, modified);
+        }
+    }
+
+    fn temp_certs(name: &str) -> (PathBuf, TlsConfig) {
+        let dir = std::env::temp_dir().join(format!("mincat-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        install(&dir, CERTS, SystemTime::now() - Duration::from_secs(60));
+        let config = TlsConfig::from_pem_file(dir.join("cert.pem"), dir.join("key.pem")).unwrap();
+        (dir, config)
+    }
+
+    #[test]
+    fn reload_serves_the_new_certificate() {
+        let (dir, config) = temp_certs("reload");
+        assert_eq!(served_cert(&config), cert_der(CERTS));
+
+        install(&dir, RENEWED_CERTS, SystemTime::now());
+        config.reload().unwrap();
+        assert_eq!(served_cert(&config), cert_der(RENEWED_CERTS));
+
+        let pem = fs::read(format!("{CERTS}/cert.pem")).unwrap();
+        write_pem(&dir, "cert.pem", &pem[..pem.len() / 2], SystemTime::now());
+        assert!(config.reload().is_err());
+        assert_eq!(served_cert(&config), cert_der(RENEWED_CERTS));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn watch_reloads_modified_certificates() {
+        let (dir, config) = temp_certs("watch");
+        let config = config.reload_interval(Duration::from_millis(20));
+        let watcher = config.watch().unwrap();
+
+        install(&dir, RENEWED_CERTS, SystemTime::now());
+        let renewed = cert_der(RENEWED_CERTS);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while served_cert(&config) != renewed {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        let pem = fs::read(format!("{CERTS}/key.pem")).unwrap();
+        write_pem(
+            &dir,
+            "key.pem",
+            &pem[..pem.len() / 2],
+            SystemTime::now() + Duration::from_secs(60),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(served_cert(&config), renewed);
+
+        watcher.abort();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn stalled_handshake_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = Listener::tls(listener, &config()).unwrap();
+        let (signal, receiver) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            App::from(Router::new())
+                .server_config(
+                    ServerConfig::new().tls_handshake_timeout(Duration::from_millis(100)),
+                )
+                .serve_with_shutdown([listener], async {
+                    let _ = receiver.await;
+                })
+                .await
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let mut buf = [0; 1];
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buf)).await;
+        assert!(matches!(read, Ok(Ok(0))));
+
+        let _ = signal.send(());
+        server.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn listener_with_reload_interval_builds_outside_a_runtime() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let listener = runtime.block_on(TcpListener::bind("127.0.0.1:0")).unwrap();
+        let config = config().reload_interval(Duration::from_millis(20));
+
+        let listener = Listener::tls(listener, &config).unwrap();
+        runtime.block_on(async move { drop(listener) });
+    }
+
+    #[get("/")]
+    async fn alpn(info: ConnectInfo) -> String {
+        info.tls
+            .and_then(|tls| tls.alpn_protocol)
+            .unwrap_or_default()
+    }
+
+    // The example certificate is self-signed with CA:TRUE, which webpki refuses as an end
+    // entity, so the client pins it instead of trusting it as a root.
+    #[derive(Debug)]
+    struct PinnedCert {
+        cert: Vec<u8>,
+        algorithms: WebPkiSupportedAlgorithms,
+    }
+
+    impl ServerCertVerifier for PinnedCert {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            _: &[CertificateDer<'_>],
+            _: &ServerName<'_>,
+            _: &[u8],
+            _: UnixTime,
+        ) -> Result<ServerCertVerified, Error> {
+            if end_entity.as_ref() == self.cert {
+                Ok(ServerCertVerified::assertion())
+            } else {
+                Err(Error::General("unexpected server certificate".to_string()))
+            }
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, Error> {
+            crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.algorithms.supported_schemes()
+        }
+    }
+
+    async fn request_with_alpn(addr: std::net::SocketAddr, protocol: &[u8]) -> String {
+        let provider = ring::default_provider();
+        let verifier = PinnedCert {
+            cert: cert_der(CERTS),
+            algorithms: provider.signature_verification_algorithms,
+        };
+        let mut config = ClientConfig::builder_with_provider(Arc::new(provider))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(verifier))
+            .with_no_client_auth();
+        config.alpn_protocols = vec![protocol.to_vec()];
+
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let stream = TlsConnector::from(Arc::new(config))
+            .connect(ServerName::try_from("localhost").unwrap(), stream)
+            .await
+            .unwrap();
+        assert_eq!(stream.get_ref().1.alpn_protocol(), Some(protocol));
+
+        let request = Request::get("/").body(Empty::<Bytes>::new()).unwrap();
+        let io = TokioIo::new(stream);
+        let response = if protocol == b"h2" {
+            let (mut sender, conn) =
+                hyper::client::conn::http2::handshake(TokioExecutor::new(), io)
+                    .await
+                    .unwrap();
+            tokio::spawn(conn);
+            sender.send_request(request).await.unwrap()
+        } else {
+            let (mut sender, conn) = hyper::client::conn::http1::handshake(io).await.unwrap();
+            tokio::spawn(conn);
+            sender.send_request(request).await.unwrap()
+        };
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn negotiates_alpn_with_rustls_clients() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = Listener::tls(listener, &config()).unwrap();
+        let (signal, receiver) = oneshot::channel::<()>();
+        let server = tokio::spawn(async move {
+            App::from(Router::new().route(alpn))
+                .serve_with_shutdown([listener], async {
+                    let _ = receiver.await;
+                })
+                .await
+        });
+
+        assert_eq!(request_with_alpn(addr, b"h2").await, "h2");
+        assert_eq!(request_with_alpn(addr, b"http/1.1").await, "http/1.1");
+
+        let _ = signal.send(());
+        server.await.unwrap().unwrap();
+    }
+}
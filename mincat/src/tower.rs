@@ -0,0 +1,380 @@
+use std::{
+    convert::Infallible,
+    future::{poll_fn, Future},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use http::StatusCode;
+use mincat_core::{
+    body::Body,
+    error::{BoxError, Error},
+    handler::HandlerFuncParam,
+    middleware::Middleware,
+    next::Next,
+    request::Request,
+    response::{IntoResponse, Response},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::App;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+#[derive(Clone)]
+pub struct NextService;
+
+impl Service<Request> for NextService {
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, mut request: Request) -> Self::Future {
+        let next = request.extensions_mut().remove::<Next>();
+        Box::pin(async move {
+            match next {
+                Some(next) => Ok(next.run(request).await),
+                None => {
+                    tracing::error!(
+                        "LayerMiddleware: the request reached NextService without its Next \
+                         extension, the layer must forward the request it was given"
+                    );
+                    Ok(StatusCode::INTERNAL_SERVER_ERROR.into_response())
+                }
+            }
+        })
+    }
+}
+
+async fn call_service<T, B>(mut service: T, request: Request) -> Response
+where
+    T: Service<Request, Response = http::Response<B>>,
+    T::Error: Into<BoxError>,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    if let Err(e) = poll_fn(|cx| service.poll_ready(cx)).await {
+        return Error::new(e).into_response();
+    }
+
+    match service.call(request).await {
+        Ok(response) => response.map(Body::new),
+        Err(e) => Error::new(e).into_response(),
+    }
+}
+
+/// Runs a tower [`Layer`] as a mincat middleware.
+///
+/// The layer is applied once, in [`LayerMiddleware::new`], around a
+/// [`NextService`]. Every request is served by a clone of the resulting
+/// service, and the [`Next`] of the request travels to the inner
+/// [`NextService`] in the request extensions. This puts some limits on the
+/// layers that can be used:
+///
+/// - The service must forward the request it receives, or at least its
+///   extensions. A layer that builds a new request without copying the
+///   extensions makes the request fail with 500 and logs an error.
+/// - The request body type must stay [`Body`]. Layers that map the request
+///   body to another type do not compile.
+/// - State is only shared between requests if the service shares it between
+///   its clones, as the tower-http layers, `ConcurrencyLimit` and `LoadShed`
+///   do. Services that keep state per instance, such as `RateLimit`, see
+///   every request on a fresh clone and must be wrapped in tower's `Buffer`.
+#[derive(Clone)]
+pub struct LayerMiddleware<T>(T);
+
+impl<T> LayerMiddleware<T> {
+    pub fn new<L>(layer: L) -> Self
+    where
+        L: Layer<NextService, Service = T>,
+    {
+        Self(layer.layer(NextService))
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, B> Middleware for LayerMiddleware<T>
+where
+    T: Service<Request, Response = http::Response<B>> + Clone + Send + Sync + 'static,
+    T::Future: Send,
+    T::Error: Into<BoxError>,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    async fn call(self: Box<Self>, mut request: Request, next: Next) -> Response {
+        request.extensions_mut().insert(next);
+        call_service(self.0, request).await
+    }
+
+    async fn call_ref(&self, mut request: Request, next: Next) -> Response {
+        request.extensions_mut().insert(next);
+        call_service(self.0.clone(), request).await
+    }
+
+    fn clone_box(&self) -> Box<dyn Middleware> {
+        Box::new(self.clone())
+    }
+}
+
+impl<T, B> From<LayerMiddleware<T>> for Box<dyn Middleware>
+where
+    T: Service<Request, Response = http::Response<B>> + Clone + Send + Sync + 'static,
+    T::Future: Send,
+    T::Error: Into<BoxError>,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    fn from(value: LayerMiddleware<T>) -> Box<dyn Middleware> {
+        value.clone_box()
+    }
+}
+
+pub struct ServiceParam;
+
+/// Runs a tower [`Service`] as a mincat handler, for a route or a fallback.
+///
+/// Like [`LayerMiddleware`], every request is served by a fresh clone of the
+/// service, and `poll_ready` is called on that clone right before the
+/// request. Readiness and other state are only shared between requests if
+/// the service shares them between its clones; services that keep them per
+/// instance must be wrapped in tower's `Buffer`.
+#[derive(Clone)]
+pub struct ServiceHandler<T>(T);
+
+impl<T> ServiceHandler<T> {
+    pub fn new(service: T) -> Self {
+        Self(service)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, B> HandlerFuncParam<ServiceParam> for ServiceHandler<T>
+where
+    T: Service<Request, Response = http::Response<B>> + Send,
+    T::Future: Send,
+    T::Error: Into<BoxError>,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    async fn call(self, request: Request) -> Response {
+        call_service(self.0, request).await
+    }
+}
+
+impl<S, B> Service<http::Request<B>> for App<S>
+where
+    S: 'static,
+    B: http_body::Body<Data = Bytes> + Send + 'static,
+    B::Error: Into<BoxError>,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = BoxFuture<Result<Response, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let dispatcher = self.dispatcher();
+        Box::pin(async move { Ok(dispatcher.dispatch(request.map(Body::new)).await) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::Infallible,
+        future::{poll_fn, ready, Ready},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll},
+    };
+
+    use http::{Method, StatusCode};
+    use http_body_util::BodyExt;
+    use mincat_core::{request::Request, response::Response, route::Route};
+    use tower_layer::Layer;
+    use tower_service::Service;
+
+    use crate::{
+        http::{get, Router},
+        test::TestClient,
+        App,
+    };
+
+    use super::{BoxFuture, LayerMiddleware, ServiceHandler};
+
+    #[derive(Clone, Default)]
+    struct CountLayer {
+        built: Arc<AtomicUsize>,
+    }
+
+    impl<S> Layer<S> for CountLayer {
+        type Service = CountService<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            self.built.fetch_add(1, Ordering::SeqCst);
+            CountService {
+                inner,
+                calls: Default::default(),
+            }
+        }
+    }
+
+    #[derive(Clone)]
+    struct CountService<S> {
+        inner: S,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl<S> Service<Request> for CountService<S>
+    where
+        S: Service<Request, Response = Response, Error = Infallible>,
+        S::Future: Send + 'static,
+    {
+        type Response = Response;
+        type Error = Infallible;
+        type Future = BoxFuture<Result<Response, Infallible>>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: Request) -> Self::Future {
+            let calls = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let future = self.inner.call(request);
+            Box::pin(async move {
+                let mut response = future.await?;
+                response.headers_mut().insert("x-calls", calls.into());
+                Ok(response)
+            })
+        }
+    }
+
+    #[get("/")]
+    async fn index() -> &'static str {
+        "index"
+    }
+
+    #[tokio::test]
+    async fn layer_is_built_once() {
+        let layer = CountLayer::default();
+        let router = Router::new()
+            .route(index)
+            .middleware(LayerMiddleware::new(layer.clone()));
+        let client = TestClient::new(router);
+
+        for calls in 1..=3 {
+            let response = client.get("/").send().await;
+            assert_eq!(response.headers()["x-calls"], calls.to_string());
+            assert_eq!(response.text().await, "index");
+        }
+
+        assert_eq!(layer.built.load(Ordering::SeqCst), 1);
+    }
+
+    #[derive(Clone)]
+    struct StripExtensions<S>(S);
+
+    impl<S> Service<Request> for StripExtensions<S>
+    where
+        S: Service<Request>,
+    {
+        type Response = S::Response;
+        type Error = S::Error;
+        type Future = S::Future;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.0.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: Request) -> Self::Future {
+            let (parts, body) = request.into_parts();
+            let mut request = Request::new(body);
+            *request.uri_mut() = parts.uri;
+            self.0.call(request)
+        }
+    }
+
+    #[tokio::test]
+    async fn request_without_next_fails() {
+        let layer = tower_layer::layer_fn(StripExtensions);
+        let router = Router::new()
+            .route(index)
+            .middleware(LayerMiddleware::new(layer));
+        let client = TestClient::new(router);
+
+        let response = client.get("/").send().await;
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[derive(Clone)]
+    struct Echo;
+
+    impl Service<Request> for Echo {
+        type Response = http::Response<String>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Infallible>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: Request) -> Self::Future {
+            ready(Ok(http::Response::new(format!(
+                "{} {}",
+                request.method(),
+                request.uri()
+            ))))
+        }
+    }
+
+    #[tokio::test]
+    async fn service_handler_serves_routes_and_fallbacks() {
+        let router = Router::new()
+            .route(index)
+            .route(Route::init(
+                Method::POST,
+                "/echo",
+                ServiceHandler::new(Echo),
+            ))
+            .fallback(ServiceHandler::new(Echo));
+        let client = TestClient::new(router);
+
+        let response = client.post("/echo").send().await;
+        assert_eq!(response.text().await, "POST /echo");
+
+        let response = client.get("/missing?page=2").send().await;
+        assert_eq!(response.text().await, "GET /missing?page=2");
+
+        let response = client.get("/").send().await;
+        assert_eq!(response.text().await, "index");
+    }
+
+    #[tokio::test]
+    async fn app_is_a_tower_service() {
+        let mut app = App::from(Router::new().route(index));
+        poll_fn(|cx| Service::<http::Request<String>>::poll_ready(&mut app, cx))
+            .await
+            .unwrap();
+
+        let request = http::Request::get("/").body(String::new()).unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, "index");
+
+        let request = http::Request::get("/missing").body(String::new()).unwrap();
+        let response = app.call(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
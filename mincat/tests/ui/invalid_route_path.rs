@@ -0,0 +1,18 @@
+use mincat::http::get;
+
+#[get("users/:id")]
+async fn missing_slash() -> &'static str {
+    "user"
+}
+
+#[get("/users/:id/:id")]
+async fn duplicate_param() -> &'static str {
+    "user"
+}
+
+#[get("/users/:id<u32")]
+async fn unclosed_constraint() -> &'static str {
+    "user"
+}
+
+fn main() {}
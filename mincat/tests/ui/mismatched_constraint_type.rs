@@ -0,0 +1,8 @@
+use mincat::http::get;
+
+#[get("/users/:id<u32>")]
+async fn user(mincat::extract::Path((id,)): mincat::extract::Path<(u64,)>) -> String {
+    format!("user {id}")
+}
+
+fn main() {}
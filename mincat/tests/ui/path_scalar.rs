@@ -0,0 +1,8 @@
+use mincat::http::get;
+
+#[get("/users/:id")]
+async fn user(mincat::extract::Path(id): mincat::extract::Path<u32>) -> String {
+    format!("user {id}")
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use mincat::{extract::PathFields, http::get};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, PathFields)]
+struct Post {
+    id: u32,
+    slug: String,
+}
+
+#[get("/posts/:id")]
+async fn show_post(mincat::extract::Path(post): mincat::extract::Path<Post>) -> String {
+    format!("{} {}", post.id, post.slug)
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use mincat::{extract::PathFields, http::get};
+use serde::Deserialize;
+
+#[derive(Clone, Deserialize, PathFields)]
+#[serde(deny_unknown_fields)]
+struct Post {
+    id: u32,
+}
+
+#[get("/posts/:id/:slug")]
+async fn show_post(mincat::extract::Path(post): mincat::extract::Path<Post>) -> String {
+    post.id.to_string()
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use mincat::http::get;
+
+#[get("/users/:id")]
+async fn user(
+    mincat::extract::Path((id, name)): mincat::extract::Path<(u32, String)>,
+) -> String {
+    format!("user {id} {name}")
+}
+
+fn main() {}
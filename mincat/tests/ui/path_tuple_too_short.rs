@@ -0,0 +1,8 @@
+use mincat::http::get;
+
+#[get("/teams/:team/members/:id")]
+async fn member(mincat::extract::Path((id,)): mincat::extract::Path<(u32,)>) -> String {
+    format!("member {id}")
+}
+
+fn main() {}
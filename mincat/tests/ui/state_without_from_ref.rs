@@ -0,0 +1,21 @@
+use mincat::{
+    extract::State,
+    http::{get, Router},
+};
+
+#[derive(Clone)]
+struct AppState;
+
+#[derive(Clone)]
+struct Config;
+
+type Shared<T> = State<T>;
+
+#[get("/config")]
+async fn config(_: Shared<Config>) -> &'static str {
+    "config"
+}
+
+fn main() {
+    Router::<AppState>::default().route(config);
+}
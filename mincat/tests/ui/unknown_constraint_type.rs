@@ -0,0 +1,13 @@
+use mincat::http::get;
+
+#[get("/users/:id<u46>")]
+async fn user() -> &'static str {
+    "user"
+}
+
+#[get("/users/:name<String>")]
+async fn user_by_name() -> &'static str {
+    "user"
+}
+
+fn main() {}